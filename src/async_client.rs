@@ -0,0 +1,431 @@
+// Description: Async (tokio) variant of the ttRPC client
+
+use crate::client::{
+    call_error, check_serving, command_request_id, decode_json_reply, encode_json_request,
+    parse_call_args, parse_cmd_line, parse_metadata_arg, parse_shutdown_args, print_commands,
+    print_json_reply, readline, restore_request_id, timeout_nano, ClientBuilder, SET_HEADER_CMD,
+    SHUTDOWN_CMD,
+};
+use crate::dynamic::{decode_error, encode_error, DescriptorPool};
+use crate::error::{Error, Result};
+use crate::health::{
+    HealthCheckRequest, HealthCheckResponse, HealthCheckResponse_ServingStatus as ServingStatus,
+    HealthWatchRequest,
+};
+use crate::healthcheck::{HEALTH_SERVICE_NAME, MY_SERVICE_NAME};
+use crate::reflect::REFLECTION_SERVICE_NAME;
+use crate::reflection::{ListServicesRequest, ListServicesResponse, ServiceInfo};
+use crate::service::{HelloReply, HelloRequest, ShutdownReply, ShutdownRequest};
+use crate::service_ttrpc_async::MyServiceClient;
+use crate::types::{Config, Metadata, ShutdownParams, REQUEST_ID_KEY};
+use protobuf::Message;
+use slog::{info, o, Logger};
+use slog_scope::GlobalLoggerGuard;
+use std::convert::TryFrom;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use ttrpc::r#async::Client;
+use ttrpc::ttrpc::Code;
+
+// A future polled with "logger" as the scope logger, so everything it
+// logs carries the logger's values, on whichever thread it resumes.
+struct Scoped<F> {
+    logger: Logger,
+    fut: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let Scoped { logger, fut } = &mut *self;
+
+        slog_scope::scope(logger, || fut.as_mut().poll(cx))
+    }
+}
+
+impl ClientBuilder {
+    /// Connect to the server using ttRPC's tokio-based async client. Must
+    /// be called from within a tokio runtime. Requires the `async`
+    /// feature.
+    pub fn build_async(self) -> Result<AsyncHelloClient> {
+        let descriptors = self.descriptor_pool()?;
+
+        let conn = self.connect()?;
+
+        let ttrpc_client = Client::new(conn.fd);
+
+        Ok(AsyncHelloClient {
+            cfg: conn.cfg,
+            client: MyServiceClient::new(ttrpc_client.clone()),
+            ttrpc_client,
+            descriptors,
+            timeout: conn.timeout,
+            metadata: Mutex::new(conn.metadata),
+            _log_guard: conn.log_guard,
+        })
+    }
+}
+
+/// An async client connected to a `MyService` ttRPC server.
+pub struct AsyncHelloClient {
+    cfg: Config,
+    client: MyServiceClient,
+    ttrpc_client: Client,
+    descriptors: DescriptorPool,
+    timeout: Option<Duration>,
+
+    // Sent with every request.
+    metadata: Mutex<Metadata>,
+
+    _log_guard: Option<GlobalLoggerGuard>,
+}
+
+impl AsyncHelloClient {
+    pub fn config(&self) -> &Config {
+        &self.cfg
+    }
+
+    /// The generated async ttRPC client, for calls not wrapped by this
+    /// type.
+    pub fn service_client(&self) -> &MyServiceClient {
+        &self.client
+    }
+
+    /// The metadata sent with every request.
+    pub fn metadata(&self) -> Metadata {
+        self.metadata.lock().unwrap().clone()
+    }
+
+    /// Send `value` as metadata `key` with every later request, replacing
+    /// any values it had.
+    pub fn set_metadata(&self, key: &str, value: &str) {
+        self.metadata
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), vec![value.to_string()]);
+    }
+
+    /// Stop sending metadata `key`.
+    pub fn remove_metadata(&self, key: &str) {
+        self.metadata.lock().unwrap().remove(key);
+    }
+
+    /// Await `fut`, sending `id` as [`REQUEST_ID_KEY`] metadata with each
+    /// request it makes and logging it with everything it logs.
+    pub async fn with_request_id<F: Future>(&self, id: &str, fut: F) -> F::Output {
+        let previous = self
+            .metadata
+            .lock()
+            .unwrap()
+            .insert(REQUEST_ID_KEY.to_string(), vec![id.to_string()]);
+
+        let result = Scoped {
+            logger: sl!().new(o!(REQUEST_ID_KEY => id.to_string())),
+            fut: Box::pin(fut),
+        }
+        .await;
+
+        restore_request_id(&self.metadata, id, previous);
+
+        result
+    }
+
+    // Make an RPC with the client's metadata, returning the serialized
+    // reply and mapping a timeout to Error::Deadline. "timeout" overrides
+    // the client-wide deadline.
+    async fn request(
+        &self,
+        service: &str,
+        method: &str,
+        payload: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>> {
+        let timeout = timeout.or(self.timeout);
+
+        let mut request = ttrpc::Request::new();
+
+        request.set_service(service.to_string());
+        request.set_method(method.to_string());
+        request.set_payload(payload);
+        request.set_timeout_nano(timeout_nano(timeout));
+        request.set_metadata(ttrpc::context::to_pb(self.metadata()));
+
+        let start = Instant::now();
+
+        match self.ttrpc_client.request(request).await {
+            Ok(reply) => Ok(reply.get_payload().to_vec()),
+            Err(e) => Err(call_error(method, timeout, start.elapsed(), e)),
+        }
+    }
+
+    // Make an RPC with a typed request and reply.
+    async fn call<Req: Message, Reply: Message>(
+        &self,
+        service: &str,
+        method: &str,
+        req: &Req,
+        timeout: Option<Duration>,
+    ) -> Result<Reply> {
+        let payload = req.write_to_bytes().map_err(encode_error)?;
+
+        let reply = self.request(service, method, payload, timeout).await?;
+
+        Reply::parse_from_bytes(&reply).map_err(decode_error)
+    }
+
+    /// Call the `SayHello` RPC, returning the server's greeting. `timeout`
+    /// overrides the client default.
+    pub async fn say_hello(&self, name: &str, timeout: Option<Duration>) -> Result<String> {
+        let mut req = HelloRequest::default();
+
+        req.set_name(name.to_owned());
+
+        info!(sl!(), "sending request to server";
+            "request" => name);
+
+        let reply: HelloReply = self
+            .call(MY_SERVICE_NAME, "SayHello", &req, timeout)
+            .await?;
+
+        info!(sl!(), "response received";
+            "response" => reply.get_message());
+
+        Ok(reply.get_message().to_string())
+    }
+
+    /// Call the `Shutdown` RPC. See [`crate::HelloClient::shutdown_with_params`].
+    pub async fn shutdown_with_params(
+        &self,
+        params: &ShutdownParams,
+        timeout: Option<Duration>,
+    ) -> Result<String> {
+        let mut req = ShutdownRequest::default();
+
+        req.set_reason(params.reason.clone());
+        req.set_force(params.force);
+
+        if let Some(grace_period) = params.grace_period {
+            req.set_grace_period_ms(u64::try_from(grace_period.as_millis()).unwrap_or(u64::MAX));
+        }
+
+        let reply: ShutdownReply = self
+            .call(MY_SERVICE_NAME, "Shutdown", &req, timeout)
+            .await?;
+
+        info!(sl!(), "response received";
+            "response" => format!("{:?}", reply));
+
+        Ok(reply.get_message().to_string())
+    }
+
+    /// Call the `Health.Check` RPC, returning the status of `service` (""
+    /// for the server as a whole).
+    pub async fn health_check(
+        &self,
+        service: &str,
+        timeout: Option<Duration>,
+    ) -> Result<ServingStatus> {
+        let mut req = HealthCheckRequest::default();
+
+        req.set_service(service.to_string());
+
+        let reply: HealthCheckResponse = self
+            .call(HEALTH_SERVICE_NAME, "Check", &req, timeout)
+            .await?;
+
+        info!(sl!(), "response received";
+            "service" => service,
+            "status" => format!("{:?}", reply.get_status()));
+
+        Ok(reply.get_status())
+    }
+
+    /// Call the `Health.Watch` RPC. See [`crate::HelloClient::health_watch`].
+    pub async fn health_watch(
+        &self,
+        service: &str,
+        last_status: ServingStatus,
+        wait: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Result<ServingStatus> {
+        let mut req = HealthWatchRequest::default();
+
+        req.set_service(service.to_string());
+        req.set_last_status(last_status);
+
+        if let Some(wait) = wait {
+            req.set_wait_ms(u64::try_from(wait.as_millis()).unwrap_or(u64::MAX));
+        }
+
+        let reply: HealthCheckResponse = self
+            .call(HEALTH_SERVICE_NAME, "Watch", &req, timeout)
+            .await?;
+
+        info!(sl!(), "response received";
+            "service" => service,
+            "status" => format!("{:?}", reply.get_status()));
+
+        Ok(reply.get_status())
+    }
+
+    /// Call the `ServerReflection.ListServices` RPC, returning the services
+    /// the server has registered.
+    pub async fn list_services(&self, timeout: Option<Duration>) -> Result<Vec<ServiceInfo>> {
+        let req = ListServicesRequest::default();
+
+        let mut reply: ListServicesResponse = self
+            .call(REFLECTION_SERVICE_NAME, "ListServices", &req, timeout)
+            .await?;
+
+        Ok(reply.take_services().into_vec())
+    }
+
+    /// Call any method described by the client's descriptors. See
+    /// [`crate::HelloClient::call_json`].
+    pub async fn call_json(
+        &self,
+        method: &str,
+        request: &str,
+        timeout: Option<Duration>,
+    ) -> Result<serde_json::Value> {
+        let (info, payload) = encode_json_request(&self.descriptors, method, request)?;
+
+        let reply = self
+            .request(&info.service, &info.method, payload, timeout)
+            .await?;
+
+        decode_json_reply(&self.descriptors, &info, &reply)
+    }
+
+    // Wait for the status of "service" to change from its current status,
+    // failing unless it changes to serving.
+    async fn watch_until_changed(&self, service: &str, timeout: Option<Duration>) -> Result<()> {
+        let last_status = match self.health_check(service, timeout).await {
+            Ok(status) => status,
+            Err(Error::RpcStatus {
+                code: Code::NOT_FOUND,
+                ..
+            }) => ServingStatus::SERVICE_UNKNOWN,
+            Err(e) => return Err(e),
+        };
+
+        // Have the server reply well before the deadline.
+        let wait = timeout.or(self.timeout).map(|t| t / 2);
+
+        loop {
+            let status = self
+                .health_watch(service, last_status, wait, timeout)
+                .await?;
+
+            if status != last_status {
+                return check_serving(service, status);
+            }
+        }
+    }
+
+    // Execute the command in "line". Returns true if the client should
+    // stop, as after a Shutdown.
+    async fn handle_cmd(&self, line: &str) -> Result<bool> {
+        let (name, timeout, args) = parse_cmd_line(line)?;
+
+        let id = command_request_id(&self.metadata());
+
+        self.with_request_id(&id, self.run_cmd(name, timeout, args))
+            .await?;
+
+        Ok(name == SHUTDOWN_CMD)
+    }
+
+    // Run command "name", given its arguments.
+    async fn run_cmd(&self, name: &str, timeout: Option<Duration>, args: &str) -> Result<()> {
+        match name {
+            "SayHello" => {
+                self.say_hello(args, timeout).await?;
+            }
+            SHUTDOWN_CMD => {
+                let params = parse_shutdown_args(args)?;
+
+                let msg = self.shutdown_with_params(&params, timeout).await?;
+
+                info!(sl!(), "server shutting down"; "message" => msg);
+            }
+            "Health" => {
+                let status = self.health_check(args, timeout).await?;
+
+                check_serving(args, status)?;
+            }
+            "HealthWatch" => self.watch_until_changed(args, timeout).await?,
+            "ListServices" => {
+                for service in self.list_services(timeout).await? {
+                    info!(sl!(), "service";
+                        "name" => service.get_name(),
+                        "methods" => service.get_methods().join(" "),
+                        "file" => service.get_file());
+                }
+            }
+            "Call" => {
+                let (method, request) = parse_call_args(args)?;
+
+                let reply = self.call_json(method, request, timeout).await?;
+
+                print_json_reply(&reply)?;
+            }
+            SET_HEADER_CMD => {
+                let (key, value) = parse_metadata_arg(args)?;
+
+                match value {
+                    "" => self.remove_metadata(key),
+                    value => self.set_metadata(key, value),
+                }
+
+                info!(sl!(), "request metadata";
+                    "metadata" => format!("{:?}", self.metadata()));
+            }
+            _ => return Err(Error::Usage(format!("Invalid command: {:?}", name))),
+        }
+
+        info!(sl!(), "Command {:} returned", name);
+
+        Ok(())
+    }
+
+    /// Run each command in turn, stopping early after a `Shutdown`.
+    pub async fn run_commands(&self, commands: &[&str]) -> Result<()> {
+        for cmd in commands {
+            if self.handle_cmd(cmd).await? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prompt for commands on stdin until the user quits.
+    pub async fn run_interactive(&self) -> Result<()> {
+        let quit = "quit";
+
+        loop {
+            print_commands(quit);
+
+            let line = readline("Enter command").map_err(Error::Io)?;
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            if line.starts_with(quit) {
+                break;
+            }
+
+            if self.handle_cmd(&line).await? {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,247 @@
+// Description: Async (tokio) variant of the ttRPC server
+
+use crate::error::{Error, Result};
+use crate::health::{HealthCheckRequest, HealthCheckResponse, HealthWatchRequest};
+use crate::health_ttrpc_async::{create_health, Health};
+use crate::healthcheck::HealthService;
+use crate::interceptor::InterceptorChain;
+use crate::reflect::ReflectionService;
+use crate::reflection::{
+    FileContainingSymbolRequest, FileDescriptorResponse, ListServicesRequest, ListServicesResponse,
+};
+use crate::reflection_ttrpc_async::{create_server_reflection, ServerReflection};
+use crate::server::{rpc_error, InFlightGuard, Listener};
+use crate::service::{HelloReply, HelloRequest, ShutdownReply, ShutdownRequest};
+use crate::service_ttrpc_async::{create_my_service, MyService};
+use crate::types::{HelloService, RequestContext};
+use crate::uri::ServerUri;
+use crate::utils::new_runtime;
+use async_trait::async_trait;
+use ttrpc::error::Result as TResult;
+use ttrpc::r#async::{MethodHandler, Server, TtrpcContext};
+use ttrpc::ttrpc::Code;
+
+use slog::{info, warn};
+use std::collections::HashMap;
+use std::os::unix::io::RawFd;
+use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+// The handler's view of a request, from the async server's context.
+fn request_context(ctx: &TtrpcContext) -> RequestContext {
+    RequestContext::new(ctx.fd, ctx.metadata.clone())
+}
+
+// Run "f", a handler that may block (such as a long poll), on tokio's
+// blocking thread pool so it does not hold up other requests.
+async fn blocking<T, F>(f: F) -> TResult<T>
+where
+    F: FnOnce() -> TResult<T> + Send + 'static,
+    T: Send + 'static,
+{
+    match tokio::task::spawn_blocking(f).await {
+        Ok(result) => result,
+        Err(e) => Err(rpc_error(
+            Code::INTERNAL,
+            format!("request handler failed: {}", e),
+        )),
+    }
+}
+
+// An async method handler whose requests count as in flight until it
+// returns, handing its reply to the server to send.
+struct TrackedMethod {
+    handler: Box<dyn MethodHandler + Send + Sync>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl MethodHandler for TrackedMethod {
+    async fn handler(&self, ctx: TtrpcContext, req: ttrpc::Request) -> TResult<(u32, Vec<u8>)> {
+        let _guard = InFlightGuard::new(&self.in_flight);
+
+        self.handler.handler(ctx, req).await
+    }
+}
+
+// Wrap each handler in "methods", the async MyService methods, so its
+// requests are counted by "service".
+fn track_methods(
+    service: &HelloService,
+    methods: HashMap<String, Box<dyn MethodHandler + Send + Sync>>,
+) -> HashMap<String, Box<dyn MethodHandler + Send + Sync>> {
+    methods
+        .into_iter()
+        .map(|(method, handler)| {
+            let tracked = TrackedMethod {
+                handler,
+                in_flight: service.in_flight_counter(&method).clone(),
+            };
+
+            (
+                method,
+                Box::new(tracked) as Box<dyn MethodHandler + Send + Sync>,
+            )
+        })
+        .collect()
+}
+
+#[async_trait]
+impl MyService for HelloService {
+    async fn say_hello(&self, ctx: &TtrpcContext, req: HelloRequest) -> TResult<HelloReply> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_say_hello(&ctx, req))
+    }
+
+    async fn shutdown(&self, ctx: &TtrpcContext, req: ShutdownRequest) -> TResult<ShutdownReply> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_shutdown(&ctx, req))
+    }
+}
+
+#[async_trait]
+impl Health for HealthService {
+    async fn check(
+        &self,
+        ctx: &TtrpcContext,
+        req: HealthCheckRequest,
+    ) -> TResult<HealthCheckResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_check(&ctx, req))
+    }
+
+    async fn watch(
+        &self,
+        ctx: &TtrpcContext,
+        req: HealthWatchRequest,
+    ) -> TResult<HealthCheckResponse> {
+        let ctx = request_context(ctx);
+
+        let health = self.clone();
+
+        blocking(move || ctx.scope(|| health.handle_watch(&ctx, req))).await
+    }
+}
+
+#[async_trait]
+impl ServerReflection for ReflectionService {
+    async fn list_services(
+        &self,
+        ctx: &TtrpcContext,
+        req: ListServicesRequest,
+    ) -> TResult<ListServicesResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_list_services(&ctx, req))
+    }
+
+    async fn file_containing_symbol(
+        &self,
+        ctx: &TtrpcContext,
+        req: FileContainingSymbolRequest,
+    ) -> TResult<FileDescriptorResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_file_containing_symbol(&ctx, req))
+    }
+}
+
+// Create an async ttRPC server for an existing listening socket bound to
+// "uri". The socket is left open on error.
+fn server_from_fd(fd: RawFd, uri: &ServerUri) -> Result<Server> {
+    let server = Server::new().add_listener(fd)?;
+
+    // The async server must be told how to accept connections on the
+    // socket, and only knows Unix and vsock sockets.
+    match uri {
+        ServerUri::UnixAbstract(_) | ServerUri::UnixPath(_) => Ok(server.set_domain_unix()),
+        ServerUri::Vsock { .. } => Ok(server.set_domain_vsock()),
+        ServerUri::Tcp { .. } => Err(Error::Usage(format!(
+            "async server cannot listen on a TCP URI (only Unix and vsock sockets are supported): {}",
+            uri
+        ))),
+        ServerUri::HybridVsock { .. } => Err(Error::Usage(format!(
+            "cannot listen on a hybrid vsock URI (the VMM owns the socket): {}",
+            uri
+        ))),
+    }
+}
+
+// The async ttRPC servers of a HelloServer, along with the tokio runtime
+// they run on.
+pub(crate) struct AsyncServers {
+    runtime: Runtime,
+    servers: Vec<Server>,
+}
+
+impl AsyncServers {
+    // Create an async ttRPC server for each listening socket, serving
+    // "service", "health" and "reflection" with "interceptors" around every
+    // method. The sockets remain the caller's to close on error.
+    pub fn new(
+        listeners: &[Listener],
+        service: &HelloService,
+        health: &HealthService,
+        reflection: &ReflectionService,
+        interceptors: &InterceptorChain,
+    ) -> Result<Self> {
+        let runtime = new_runtime()?;
+
+        let s = Box::new(service.clone()) as Box<dyn MyService + Send + Sync>;
+        let s = Arc::new(s);
+
+        let h = Box::new(health.clone()) as Box<dyn Health + Send + Sync>;
+        let h = Arc::new(h);
+
+        let r = Box::new(reflection.clone()) as Box<dyn ServerReflection + Send + Sync>;
+        let r = Arc::new(r);
+
+        let mut servers = Vec::new();
+
+        for (fd, uri) in listeners {
+            let server = server_from_fd(*fd, uri)?
+                .register_service(track_methods(
+                    service,
+                    interceptors.wrap_async(create_my_service(s.clone())),
+                ))
+                .register_service(interceptors.wrap_async(create_health(h.clone())))
+                .register_service(interceptors.wrap_async(create_server_reflection(r.clone())));
+
+            servers.push(server);
+        }
+
+        info!(sl!(), "using async server"; "servers" => servers.len());
+
+        Ok(AsyncServers { runtime, servers })
+    }
+
+    // Start accepting connections on the runtime's threads.
+    pub fn start(&mut self) -> Result<()> {
+        let servers = &mut self.servers;
+
+        self.runtime.block_on(async {
+            for server in servers.iter_mut() {
+                server.start().await?;
+            }
+
+            Ok(())
+        })
+    }
+
+    pub fn shutdown(mut self) {
+        let servers = &mut self.servers;
+
+        self.runtime.block_on(async {
+            for server in servers.iter_mut() {
+                if let Err(e) = server.shutdown().await {
+                    warn!(sl!(), "failed to shut down async server";
+                        "error" => format!("{:?}", e));
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,160 @@
+// Description: Peer credential based authorization of RPC methods
+
+use crate::error::{Error, Result};
+use nix::sys::socket::{getsockname, getsockopt, sockopt, SockAddr};
+use std::collections::HashMap;
+use std::fmt;
+use std::os::unix::io::RawFd;
+
+const UID_PREFIX: &str = "uid:";
+const GID_PREFIX: &str = "gid:";
+
+/// The identity of the process at the other end of a Unix socket, as
+/// reported by `SO_PEERCRED`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeerCred {
+    pub pid: i32,
+    pub uid: u32,
+    pub gid: u32,
+}
+
+impl PeerCred {
+    /// Returns the credentials of the peer connected to `fd`, or `None` if
+    /// `fd` is not a Unix socket (vsock and TCP peers have no credentials).
+    pub fn from_fd(fd: RawFd) -> Option<PeerCred> {
+        match getsockname(fd) {
+            Ok(SockAddr::Unix(_)) => (),
+            _ => return None,
+        }
+
+        match getsockopt(fd, sockopt::PeerCredentials) {
+            Ok(cred) => Some(PeerCred {
+                pid: cred.pid(),
+                uid: cred.uid(),
+                gid: cred.gid(),
+            }),
+            Err(_) => None,
+        }
+    }
+}
+
+impl fmt::Display for PeerCred {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "pid={} uid={} gid={}", self.pid, self.uid, self.gid)
+    }
+}
+
+/// A caller allowed to use a method.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessRule {
+    /// Processes running as this user ID.
+    Uid(u32),
+
+    /// Processes running with this (primary) group ID.
+    Gid(u32),
+}
+
+impl AccessRule {
+    fn matches(self, peer: &PeerCred) -> bool {
+        match self {
+            AccessRule::Uid(uid) => peer.uid == uid,
+            AccessRule::Gid(gid) => peer.gid == gid,
+        }
+    }
+}
+
+impl fmt::Display for AccessRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AccessRule::Uid(uid) => write!(f, "{}{}", UID_PREFIX, uid),
+            AccessRule::Gid(gid) => write!(f, "{}{}", GID_PREFIX, gid),
+        }
+    }
+}
+
+/// Which callers may use each RPC method.
+///
+/// Methods without any rules may be called by anyone. A method with rules
+/// may only be called over a Unix socket by a peer matching at least one
+/// of them, since other transports provide no peer credentials.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AccessPolicy {
+    rules: HashMap<String, Vec<AccessRule>>,
+}
+
+impl AccessPolicy {
+    pub fn new() -> Self {
+        AccessPolicy::default()
+    }
+
+    /// Allow callers matching `rule` to use `method` (for example
+    /// `"Shutdown"`), restricting the method to the allowed callers.
+    pub fn allow(mut self, method: &str, rule: AccessRule) -> Self {
+        self.rules.entry(method.to_string()).or_default().push(rule);
+        self
+    }
+
+    /// Add the rules from a specification of the form
+    /// `Method=uid:N[,gid:N...]`, for example `Shutdown=uid:0`.
+    pub fn allow_spec(mut self, spec: &str) -> Result<Self> {
+        let (method, rules) = match spec.find('=') {
+            Some(i) => (&spec[..i], &spec[i + 1..]),
+            None => {
+                return Err(Error::Usage(format!(
+                    "invalid access rule {:?} (expected 'Method=uid:N' or 'Method=gid:N')",
+                    spec
+                )))
+            }
+        };
+
+        if method.is_empty() {
+            return Err(Error::Usage(format!(
+                "access rule {:?} has no method",
+                spec
+            )));
+        }
+
+        for rule in rules.split(',') {
+            let rule = if let Some(uid) = rule.strip_prefix(UID_PREFIX) {
+                uid.parse().map(AccessRule::Uid)
+            } else if let Some(gid) = rule.strip_prefix(GID_PREFIX) {
+                gid.parse().map(AccessRule::Gid)
+            } else {
+                return Err(Error::Usage(format!(
+                    "invalid access rule {:?} in {:?} (expected 'uid:N' or 'gid:N')",
+                    rule, spec
+                )));
+            };
+
+            match rule {
+                Ok(r) => self = self.allow(method, r),
+                Err(e) => {
+                    return Err(Error::Usage(format!(
+                        "invalid ID in access rule {:?}: {}",
+                        spec, e
+                    )))
+                }
+            }
+        }
+
+        Ok(self)
+    }
+
+    /// The methods that have rules.
+    pub fn methods(&self) -> Vec<&str> {
+        self.rules.keys().map(|m| m.as_str()).collect()
+    }
+
+    /// Returns true if `peer` may call `method`.
+    pub fn is_allowed(&self, method: &str, peer: Option<&PeerCred>) -> bool {
+        let rules = match self.rules.get(method) {
+            Some(r) => r,
+            None => return true,
+        };
+
+        match peer {
+            Some(peer) => rules.iter().any(|r| r.matches(peer)),
+            None => false,
+        }
+    }
+}
@@ -1,31 +1,72 @@
 // Description: Client side of ttRPC comms
 
-use crate::service::{HelloRequest, ShutdownRequest};
-use crate::service_ttrpc::MyServiceClient;
-use crate::types::{Config, HelloService};
-use nix::sys::socket::{
-    connect, socket, AddressFamily, SockAddr, SockFlag, SockType, UnixAddr, VsockAddr,
+use crate::auth::AccessPolicy;
+use crate::dynamic::{decode_error, encode_error, DescriptorPool, MethodInfo};
+use crate::error::{Error, Result};
+use crate::health::{
+    HealthCheckRequest, HealthCheckResponse, HealthCheckResponse_ServingStatus as ServingStatus,
+    HealthWatchRequest,
+};
+use crate::healthcheck::{HEALTH_SERVICE_NAME, MY_SERVICE_NAME};
+use crate::reflect::REFLECTION_SERVICE_NAME;
+use crate::reflection::{
+    FileContainingSymbolRequest, FileDescriptorResponse, ListServicesRequest, ListServicesResponse,
+    ServiceInfo,
 };
-use slog::info;
+use crate::service::{HelloReply, HelloRequest, ShutdownReply, ShutdownRequest};
+use crate::service_ttrpc::MyServiceClient;
+use crate::socket::{abstract_sock_addr, abstract_socket_name, AbstractNaming};
+use crate::types::{Config, Metadata, RetryPolicy, ShutdownParams, REQUEST_ID_KEY};
+use crate::uri::ServerUri;
+use crate::utils::{new_request_id, parse_duration};
+use nix::sys::socket::{connect, socket, AddressFamily, SockAddr, SockFlag, SockType, VsockAddr};
+use nix::unistd::close;
+use protobuf::Message;
+use slog::{error, info, o, warn, Logger};
+use slog_scope::GlobalLoggerGuard;
+use std::convert::TryFrom;
 use std::io;
-use std::io::Write;
+use std::io::{Read, Write};
+use std::net::TcpStream;
 use std::os::unix::io::{IntoRawFd, RawFd};
 use std::os::unix::net::UnixStream;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::thread;
+use std::time::{Duration, Instant};
 use ttrpc::client::Client;
+use ttrpc::ttrpc::Code;
 use vsock::VsockStream;
 
-pub type Result<T> = std::result::Result<T, String>;
+type FP = fn(client: &HelloClient, timeout: Option<Duration>, data: &str) -> Result<()>;
 
-type FP = fn(cfg: &Config, client: &MyServiceClient, data: &str) -> Result<()>;
+// A ttRPC timeout of zero means "wait forever".
+const NO_TIMEOUT_NANO: i64 = 0;
 
-const TIMEOUT_NANO: i64 = 0;
+// How long to wait for the VMM to reply to a hybrid vsock "CONNECT".
+const HVSOCK_HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+// Longest valid hybrid vsock reply ("OK <u32>\n").
+const HVSOCK_MAX_REPLY_LEN: usize = 32;
+
+// Separates a command name from its deadline override ("SayHello@500ms").
+const TIMEOUT_SEPARATOR: char = '@';
 
 struct Cmd {
     name: &'static str,
     fp: FP,
 }
 
-const SHUTDOWN_CMD: &str = "Shutdown";
+pub(crate) const SHUTDOWN_CMD: &str = "Shutdown";
+
+// Arguments of the Shutdown command that precede the (optional) reason.
+const SHUTDOWN_FORCE_ARG: &str = "force";
+const SHUTDOWN_GRACE_PREFIX: &str = "grace=";
+
+// Sets (or with an empty value, stops sending) a request metadata key:
+// "set-header key=value".
+pub(crate) const SET_HEADER_CMD: &str = "set-header";
+const METADATA_SEPARATOR: char = '=';
 
 static CMDS: &[Cmd] = &[
     Cmd {
@@ -36,6 +77,26 @@ static CMDS: &[Cmd] = &[
         name: SHUTDOWN_CMD,
         fp: cmd_shutdown,
     },
+    Cmd {
+        name: "Health",
+        fp: cmd_health,
+    },
+    Cmd {
+        name: "HealthWatch",
+        fp: cmd_health_watch,
+    },
+    Cmd {
+        name: "ListServices",
+        fp: cmd_list_services,
+    },
+    Cmd {
+        name: "Call",
+        fp: cmd_call,
+    },
+    Cmd {
+        name: SET_HEADER_CMD,
+        fp: cmd_set_header,
+    },
 ];
 
 fn get_cmd_names() -> Vec<String> {
@@ -55,7 +116,7 @@ fn get_cmd_func(name: &str) -> Result<FP> {
         }
     }
 
-    Err(format!("Invalid command: {:?}", name))
+    Err(Error::Usage(format!("Invalid command: {:?}", name)))
 }
 
 fn client_create_vsock_fd_with_vsock_crate(cid: libc::c_uint, port: u32) -> Result<RawFd> {
@@ -66,7 +127,7 @@ fn client_create_vsock_fd_with_vsock_crate(cid: libc::c_uint, port: u32) -> Resu
 
     let stream = match VsockStream::connect(&sock_addr) {
         Ok(s) => s,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::Vsock(e.to_string())),
     };
 
     let fd = stream.into_raw_fd();
@@ -84,14 +145,17 @@ fn client_create_vsock_fd_with_nix_crate(cid: libc::c_uint, port: u32) -> Result
         None,
     ) {
         Ok(fd) => fd,
-        Err(e) => return Err(e.to_string()),
+        Err(e) => return Err(Error::Vsock(e.to_string())),
     };
 
     let sock_addr = SockAddr::new_vsock(cid, port);
 
     match connect(fd, &sock_addr) {
         Ok(_) => (),
-        Err(e) => return Err(e.to_string()),
+        Err(e) => {
+            let _ = close(fd);
+            return Err(Error::Vsock(e.to_string()));
+        }
     };
 
     Ok(fd)
@@ -109,208 +173,924 @@ fn client_create_vsock_fd(
     }
 }
 
-fn client_create_fd(
-    server_uri: &str,
-    force_abstract_socket: bool,
-    use_vsock_crate_for_vsock: bool,
-) -> Result<RawFd> {
-    // Cribbed from ttrpc:server.rs
-    let hostv: Vec<&str> = server_uri.trim().split("://").collect();
+// Connect to an abstract Unix socket using a single naming convention.
+fn client_connect_abstract_fd(name: &str, naming: AbstractNaming) -> Result<RawFd> {
+    let fd = match socket(
+        AddressFamily::Unix,
+        SockType::Stream,
+        SockFlag::empty(),
+        None,
+    ) {
+        Ok(s) => s,
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "Failed to create Unix Domain socket: {:?}",
+                e
+            )))
+        }
+    };
+
+    let sock_addr = match abstract_sock_addr(name, naming) {
+        Ok(a) => a,
+        Err(e) => {
+            let _ = close(fd);
+            return Err(e);
+        }
+    };
+
+    match connect(fd, &sock_addr) {
+        Ok(_) => (),
+        Err(e) => {
+            let _ = close(fd);
+
+            return Err(Error::Connect(format!(
+                "Failed to connect to Unix Domain abstract socket {:?} ({} naming): {:?}",
+                abstract_socket_name(name, naming),
+                naming,
+                e
+            )));
+        }
+    };
 
-    if hostv.len() != 2 {
-        return Err(format!("Invalid URI: {:?}", server_uri));
+    Ok(fd)
+}
+
+// Connect to an abstract Unix socket, trying each naming convention in turn.
+fn client_create_abstract_fd(name: &str, naming: AbstractNaming) -> Result<RawFd> {
+    let mut last_err = None;
+
+    for convention in naming.conventions() {
+        match client_connect_abstract_fd(name, *convention) {
+            Ok(fd) => {
+                info!(sl!(), "connected to abstract socket";
+                    "abstract-name" => format!("@{}", abstract_socket_name(name, *convention).escape_default()),
+                    "naming" => convention.to_string());
+
+                return Ok(fd);
+            }
+            Err(e) => {
+                info!(sl!(), "failed to connect to abstract socket";
+                    "naming" => convention.to_string(),
+                    "error" => e.to_string());
+
+                last_err = Some(e);
+            }
+        }
     }
 
-    let scheme = hostv[0].to_lowercase();
+    Err(last_err
+        .unwrap_or_else(|| Error::Connect(format!("no naming conventions to try for {:?}", name))))
+}
 
-    let fd: RawFd;
+// Connect to a guest vsock port via a hybrid vsock Unix socket on the host
+// (as provided by Firecracker and Cloud Hypervisor). The VMM expects
+// "CONNECT <port>\n" and replies "OK <host-port>\n" once the guest has
+// accepted the connection.
+fn client_create_hvsock_fd(path: &str, port: u32) -> Result<RawFd> {
+    let mut stream = match UnixStream::connect(path) {
+        Ok(s) => s,
+        Err(e) => {
+            return Err(Error::Connect(format!(
+                "failed to connect to hybrid vsock socket {:?}: {:?}",
+                path, e
+            )))
+        }
+    };
 
-    match scheme.as_str() {
-        "unix" => {
-            let mut abstract_socket = force_abstract_socket;
+    let handshake = |stream: &mut UnixStream| -> io::Result<Vec<u8>> {
+        stream.set_read_timeout(Some(HVSOCK_HANDSHAKE_TIMEOUT))?;
 
-            let mut path = hostv[1].to_string();
+        stream.write_all(format!("CONNECT {}\n", port).as_bytes())?;
 
-            if path.starts_with('@') || abstract_socket {
-                abstract_socket = true;
+        // Read a byte at a time so that no ttRPC data following the
+        // reply is consumed.
+        let mut reply = Vec::new();
+        let mut byte = [0u8; 1];
 
-                // XXX: ESSENTIAL! Add a trailing terminator
-                // XXX: as this is what the "ttrpc" crate does!
-                path = path + &"\x00".to_string();
+        while reply.len() < HVSOCK_MAX_REPLY_LEN {
+            if stream.read(&mut byte)? == 0 {
+                break;
             }
 
-            if abstract_socket {
-                fd = match socket(
-                    AddressFamily::Unix,
-                    SockType::Stream,
-                    SockFlag::empty(),
-                    None,
-                ) {
-                    Ok(s) => s,
-                    Err(e) => return Err(format!("Failed to create Unix Domain socket: {:?}", e)),
-                };
-
-                let mut unix_addr = match UnixAddr::new_abstract(path.as_bytes()) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        return Err(format!(
-                            "Failed to create Unix Domain abstract socket: {:?}",
-                            e
-                        ))
-                    }
-                };
-
-                // FIXME: Fix BUG: https://github.com/nix-rust/nix/pull/1120/
-                //
-                // All versions of the nix package prior to 0.16.0 contained a bug
-                // where the length of the path specified to new_abstract() was not
-                // calculated - nix assumed the maximum path length. This meant the
-                // path contained trailing nulls which is perfectly valid for an
-                // abstract socket... it just means you cannot connect to it from
-                // well-behaved programs that correctly handle the path length!!
-                //
-                // The problem for this program is that it currently relies on the
-                // vsock crate, which depends on "nix = 0.15.1".
-                unix_addr.1 = path.len() + 1;
-
-                let sock_addr = SockAddr::Unix(unix_addr);
-
-                match connect(fd, &sock_addr) {
-                    Ok(_) => (),
-                    Err(e) => {
-                        return Err(format!(
-                            "Failed to connect to Unix Domain abstract socket: {:?}",
-                            e
-                        ))
-                    }
-                };
-            } else {
-                let stream = match UnixStream::connect(path) {
-                    Ok(s) => s,
-                    Err(e) => {
-                        return Err(format!(
-                            "failed to create named UNIX Domain stream socket: {:?}",
-                            e
-                        ))
-                    }
-                };
-
-                fd = stream.into_raw_fd();
+            reply.push(byte[0]);
+
+            if byte[0] == b'\n' {
+                break;
             }
         }
-        "vsock" => {
-            let addr: Vec<&str> = hostv[1].split(':').collect();
-            if addr.len() != 2 {
-                return Err(format!("Invalid VSOCK URI: {:?}", server_uri));
-            }
 
-            let cid: u32 = match addr[0] {
-                "-1" => libc::VMADDR_CID_ANY,
-                "" => libc::VMADDR_CID_ANY,
-                _ => match addr[0].parse::<u32>() {
-                    Ok(c) => c,
-                    Err(e) => return Err(format!("VSOCK cid is not numeric: {:?}", e)),
-                },
+        stream.set_read_timeout(None)?;
+
+        Ok(reply)
+    };
+
+    let reply = match handshake(&mut stream) {
+        Ok(r) => r,
+        Err(e) => {
+            return Err(Error::Vsock(format!(
+                "hybrid vsock handshake with {:?} for port {} failed: {:?}",
+                path, port, e
+            )))
+        }
+    };
+
+    let reply = String::from_utf8_lossy(&reply);
+
+    let host_port = match reply
+        .strip_suffix('\n')
+        .and_then(|r| r.strip_prefix("OK "))
+        .map(|p| p.trim().parse::<u32>())
+    {
+        Some(Ok(p)) => p,
+        _ => {
+            return Err(Error::Vsock(format!(
+                "unexpected hybrid vsock reply from {:?} for port {}: {:?}",
+                path, port, reply
+            )))
+        }
+    };
+
+    info!(sl!(), "hybrid vsock connection established";
+        "path" => path,
+        "port" => port,
+        "host-port" => host_port);
+
+    Ok(stream.into_raw_fd())
+}
+
+pub fn client_create_fd(
+    server_uri: &ServerUri,
+    abstract_naming: AbstractNaming,
+    use_vsock_crate_for_vsock: bool,
+) -> Result<RawFd> {
+    let fd = match server_uri {
+        ServerUri::UnixAbstract(name) => client_create_abstract_fd(name, abstract_naming)?,
+        ServerUri::UnixPath(path) => {
+            let stream = match UnixStream::connect(path) {
+                Ok(s) => s,
+                Err(e) => {
+                    return Err(Error::Connect(format!(
+                        "failed to create named UNIX Domain stream socket: {:?}",
+                        e
+                    )))
+                }
             };
 
-            let port: u32 = match addr[1].parse::<u32>() {
-                Ok(r) => r,
-                Err(e) => return Err(format!("VSOCK port is not numeric: {:?}", e)),
+            stream.into_raw_fd()
+        }
+        ServerUri::Vsock { cid, port } => {
+            client_create_vsock_fd(use_vsock_crate_for_vsock, *cid, *port)?
+        }
+        ServerUri::HybridVsock { path, port } => client_create_hvsock_fd(path, *port)?,
+        ServerUri::Tcp { host, port } => {
+            let stream = match TcpStream::connect((host.as_str(), *port)) {
+                Ok(s) => s,
+                Err(e) => {
+                    return Err(Error::Connect(format!(
+                        "failed to connect to TCP address {}: {:?}",
+                        server_uri, e
+                    )))
+                }
             };
 
-            fd = client_create_vsock_fd(use_vsock_crate_for_vsock, cid, port)?;
+            // ttRPC messages are small, so don't delay them.
+            if let Err(e) = stream.set_nodelay(true) {
+                return Err(Error::Socket(format!("failed to set TCP_NODELAY: {:?}", e)));
+            }
+
+            stream.into_raw_fd()
         }
-        _ => return Err(format!("invalid address scheme: {:?}", server_uri)),
     };
 
     Ok(fd)
 }
 
-pub fn client(service: &HelloService, commands: Vec<&str>) -> Result<()> {
-    info!(sl!(), "starting");
+// Add the number of connection attempts to a connection error.
+fn with_attempts(e: Error, attempts: u32) -> Error {
+    let annotate = |msg: String| format!("{} (gave up after {} attempts)", msg, attempts);
 
-    let svc_ref = service.cfg.clone();
-    let cfg = svc_ref.lock().unwrap();
+    match e {
+        Error::Socket(msg) => Error::Socket(annotate(msg)),
+        Error::Connect(msg) => Error::Connect(annotate(msg)),
+        Error::Vsock(msg) => Error::Vsock(annotate(msg)),
+        e => e,
+    }
+}
 
-    let addr = &cfg.server_uri;
+// Call client_create_fd() until it succeeds or the retry policy is
+// exhausted.
+fn client_create_fd_with_retry(cfg: &Config, policy: &RetryPolicy) -> Result<RawFd> {
+    let start = Instant::now();
+    let max_attempts = policy.max_attempts.max(1);
 
-    let fd = match client_create_fd(
-        addr,
-        cfg.force_abstract_socket,
-        cfg.use_vsock_crate_for_vsock,
-    ) {
-        Ok(fd) => fd,
-        Err(e) => return Err(format!("failed to create client fd: {:?}", e)),
+    let mut backoff = policy.initial_backoff;
+    let mut attempt: u32 = 0;
+
+    loop {
+        attempt += 1;
+
+        info!(sl!(), "connecting to server";
+            "server-address" => cfg.server_uri.to_string(),
+            "attempt" => attempt,
+            "max-attempts" => max_attempts);
+
+        let e = match client_create_fd(
+            &cfg.server_uri,
+            cfg.abstract_naming,
+            cfg.use_vsock_crate_for_vsock,
+        ) {
+            Ok(fd) => return Ok(fd),
+            Err(e) => e,
+        };
+
+        let elapsed = start.elapsed();
+
+        let remaining = match policy.deadline {
+            Some(d) if elapsed >= d => Duration::from_secs(0),
+            Some(d) => d - elapsed,
+            None => backoff,
+        };
+
+        if attempt >= max_attempts || remaining == Duration::from_secs(0) {
+            return Err(with_attempts(e, attempt));
+        }
+
+        let delay = backoff.min(remaining);
+
+        warn!(sl!(), "connection attempt failed";
+            "attempt" => attempt,
+            "error" => e.to_string(),
+            "retry-in" => format!("{:?}", delay));
+
+        thread::sleep(delay);
+
+        backoff = (backoff * 2).min(policy.max_backoff);
+    }
+}
+
+// Put back the request ID metadata as it was before "id" was set for a
+// command, unless the command changed it.
+pub(crate) fn restore_request_id(
+    metadata: &Mutex<Metadata>,
+    id: &str,
+    previous: Option<Vec<String>>,
+) {
+    let mut metadata = metadata.lock().unwrap();
+
+    if metadata.get(REQUEST_ID_KEY) != Some(&vec![id.to_string()]) {
+        return;
+    }
+
+    match previous {
+        Some(values) => metadata.insert(REQUEST_ID_KEY.to_string(), values),
+        None => metadata.remove(REQUEST_ID_KEY),
     };
+}
 
-    let ttrpc_client = Client::new(fd);
+// The request ID for the next command: the one set as metadata, if any,
+// else a new one.
+pub(crate) fn command_request_id(metadata: &Metadata) -> String {
+    metadata
+        .get(REQUEST_ID_KEY)
+        .and_then(|values| values.first())
+        .filter(|id| !id.is_empty())
+        .cloned()
+        .unwrap_or_else(new_request_id)
+}
 
-    let client = MyServiceClient::new(ttrpc_client);
+// The ttRPC deadline for "timeout".
+pub(crate) fn timeout_nano(timeout: Option<Duration>) -> i64 {
+    match timeout {
+        Some(t) => i64::try_from(t.as_nanos()).unwrap_or(i64::MAX),
+        None => NO_TIMEOUT_NANO,
+    }
+}
 
-    info!(sl!(), "setup complete";
-        "server-address" => addr);
+// Convert the error from a call to "method", which took "elapsed", mapping
+// a timeout to Error::Deadline.
+pub(crate) fn call_error(
+    method: &str,
+    timeout: Option<Duration>,
+    elapsed: Duration,
+    e: ttrpc::error::Error,
+) -> Error {
+    let expired = match &e {
+        ttrpc::error::Error::RpcStatus(s) => s.get_code() == Code::DEADLINE_EXCEEDED,
+        _ => match timeout {
+            Some(t) => elapsed >= t,
+            None => false,
+        },
+    };
 
-    if cfg.interactive {
-        return interactive_client_loop(&cfg, &client);
+    if !expired {
+        return e.into();
     }
 
-    for cmd in commands {
-        let (result, shutdown) = handle_cmd(&cfg, &client, &cmd);
-        if result.is_err() {
-            return result;
+    error!(sl!(), "RPC deadline exceeded";
+        "method" => method,
+        "elapsed" => format!("{:?}", elapsed),
+        "timeout" => format!("{:?}", timeout));
+
+    Error::Deadline {
+        method: method.to_string(),
+        elapsed,
+    }
+}
+
+/// Builder for a [`HelloClient`] connected to a ttRPC server.
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    server_uri: String,
+    force_abstract_socket: bool,
+    abstract_naming: AbstractNaming,
+    use_vsock_crate_for_vsock: bool,
+    timeout: Option<Duration>,
+    retry_policy: RetryPolicy,
+    descriptor_sets: Vec<PathBuf>,
+    metadata: Metadata,
+    logger: Option<Logger>,
+}
+
+impl ClientBuilder {
+    pub fn new() -> Self {
+        ClientBuilder {
+            use_vsock_crate_for_vsock: true,
+            ..Default::default()
         }
+    }
 
-        if shutdown {
-            break;
+    /// Server URI to connect to (`unix:///some/where` or `vsock://cid:port`).
+    pub fn server_uri(mut self, server_uri: &str) -> Self {
+        self.server_uri = server_uri.to_string();
+        self
+    }
+
+    /// Treat Unix socket paths as abstract, even without an `@` prefix.
+    pub fn abstract_socket(mut self, force_abstract_socket: bool) -> Self {
+        self.force_abstract_socket = force_abstract_socket;
+        self
+    }
+
+    /// How abstract socket names are encoded (default: the "ttrpc" crate's
+    /// trailing-NUL convention).
+    pub fn abstract_naming(mut self, abstract_naming: AbstractNaming) -> Self {
+        self.abstract_naming = abstract_naming;
+        self
+    }
+
+    /// If true, use the "vsock" crate, else use the "nix" crate for vsock
+    /// comms.
+    pub fn use_vsock_crate(mut self, use_vsock_crate_for_vsock: bool) -> Self {
+        self.use_vsock_crate_for_vsock = use_vsock_crate_for_vsock;
+        self
+    }
+
+    /// Default deadline for each RPC. `None` (or a zero duration) waits
+    /// forever.
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.timeout = timeout.filter(|t| *t != Duration::from_secs(0));
+        self
+    }
+
+    /// How to retry connecting if the server is not yet available. By
+    /// default, only one attempt is made.
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Serialized `FileDescriptorSet` describing services to call with
+    /// [`HelloClient::call_json`], in addition to the embedded ones. Call
+    /// more than once to load several.
+    pub fn descriptor_set<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.descriptor_sets.push(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Metadata to send with every request. Call more than once to send
+    /// several values, or values for several keys.
+    pub fn metadata(mut self, key: &str, value: &str) -> Self {
+        self.metadata
+            .entry(key.to_string())
+            .or_default()
+            .push(value.to_string());
+        self
+    }
+
+    /// Install a child of `logger` as the global logger for as long as the
+    /// client exists.
+    pub fn logger(mut self, logger: &Logger) -> Self {
+        self.logger = Some(logger.clone());
+        self
+    }
+
+    // Connect to the server, installing the logger first so the
+    // connection attempts are logged.
+    pub(crate) fn connect(&self) -> Result<Connection> {
+        if self.server_uri.is_empty() {
+            return Err(Error::Usage("need server URI".to_string()));
+        }
+
+        let log_guard = self.logger.as_ref().map(|l| {
+            slog_scope::set_global_logger(l.new(o!("subsystem" => "ttrpc", "type" => "client")))
+        });
+
+        info!(sl!(), "starting");
+
+        let mut server_uri: ServerUri = self.server_uri.parse()?;
+
+        if self.force_abstract_socket {
+            server_uri = server_uri.into_abstract();
         }
+
+        let cfg = Config {
+            server_uri,
+            interactive: false,
+            force_abstract_socket: self.force_abstract_socket,
+            abstract_naming: self.abstract_naming,
+            allow_remote_tcp: false,
+            use_vsock_crate_for_vsock: self.use_vsock_crate_for_vsock,
+            access_policy: AccessPolicy::default(),
+            shutdown_grace_period: Duration::default(),
+            tx: None,
+        };
+
+        let fd = client_create_fd_with_retry(&cfg, &self.retry_policy)?;
+
+        info!(sl!(), "setup complete";
+            "server-address" => cfg.server_uri.to_string());
+
+        Ok(Connection {
+            cfg,
+            fd,
+            timeout: self.timeout,
+            metadata: self.metadata.clone(),
+            log_guard,
+        })
     }
 
-    Ok(())
+    // The descriptors of the embedded services and of any descriptor sets
+    // given, used by the Call command.
+    pub(crate) fn descriptor_pool(&self) -> Result<DescriptorPool> {
+        let mut descriptors = DescriptorPool::embedded();
+
+        for path in &self.descriptor_sets {
+            descriptors.add_descriptor_set(path)?;
+        }
+
+        Ok(descriptors)
+    }
+
+    /// Connect to the server.
+    pub fn build(self) -> Result<HelloClient> {
+        let descriptors = self.descriptor_pool()?;
+
+        let conn = self.connect()?;
+
+        let ttrpc_client = Client::new(conn.fd);
+
+        let client = MyServiceClient::new(ttrpc_client.clone());
+
+        Ok(HelloClient {
+            cfg: conn.cfg,
+            client,
+            ttrpc_client,
+            descriptors,
+            timeout: conn.timeout,
+            metadata: Mutex::new(conn.metadata),
+            _log_guard: conn.log_guard,
+        })
+    }
+}
+
+// A connected socket, from which the sync or async ttRPC client is
+// created.
+pub(crate) struct Connection {
+    pub cfg: Config,
+    pub fd: RawFd,
+    pub timeout: Option<Duration>,
+    pub metadata: Metadata,
+    pub log_guard: Option<GlobalLoggerGuard>,
+}
+
+/// A client connected to a `MyService` ttRPC server.
+pub struct HelloClient {
+    cfg: Config,
+    client: MyServiceClient,
+    ttrpc_client: Client,
+    descriptors: DescriptorPool,
+    timeout: Option<Duration>,
+
+    // Sent with every request.
+    metadata: Mutex<Metadata>,
+
+    _log_guard: Option<GlobalLoggerGuard>,
+}
+
+impl HelloClient {
+    pub fn config(&self) -> &Config {
+        &self.cfg
+    }
+
+    /// The generated ttRPC client, for calls not wrapped by this type.
+    pub fn service_client(&self) -> &MyServiceClient {
+        &self.client
+    }
+
+    /// The metadata sent with every request.
+    pub fn metadata(&self) -> Metadata {
+        self.metadata.lock().unwrap().clone()
+    }
+
+    /// Send `value` as metadata `key` with every later request, replacing
+    /// any values it had.
+    pub fn set_metadata(&self, key: &str, value: &str) {
+        self.metadata
+            .lock()
+            .unwrap()
+            .insert(key.to_string(), vec![value.to_string()]);
+    }
+
+    /// Stop sending metadata `key`.
+    pub fn remove_metadata(&self, key: &str) {
+        self.metadata.lock().unwrap().remove(key);
+    }
+
+    /// Call `f`, sending `id` as [`REQUEST_ID_KEY`] metadata with each
+    /// request it makes and logging it with everything it logs.
+    pub fn with_request_id<T, F: FnOnce() -> T>(&self, id: &str, f: F) -> T {
+        let previous = self
+            .metadata
+            .lock()
+            .unwrap()
+            .insert(REQUEST_ID_KEY.to_string(), vec![id.to_string()]);
+
+        let result = slog_scope::scope(&sl!().new(o!(REQUEST_ID_KEY => id.to_string())), f);
+
+        restore_request_id(&self.metadata, id, previous);
+
+        result
+    }
+
+    // Make an RPC with the client's metadata, returning the serialized
+    // reply and mapping a timeout to Error::Deadline. "timeout" overrides
+    // the client-wide deadline.
+    fn request(
+        &self,
+        service: &str,
+        method: &str,
+        payload: Vec<u8>,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>> {
+        let timeout = timeout.or(self.timeout);
+
+        let mut req = ttrpc::Request::new();
+
+        req.set_service(service.to_string());
+        req.set_method(method.to_string());
+        req.set_payload(payload);
+        req.set_timeout_nano(timeout_nano(timeout));
+        req.set_metadata(ttrpc::context::to_pb(self.metadata()));
+
+        let start = Instant::now();
+
+        match self.ttrpc_client.request(req) {
+            Ok(reply) => Ok(reply.get_payload().to_vec()),
+            Err(e) => Err(call_error(method, timeout, start.elapsed(), e)),
+        }
+    }
+
+    // Make an RPC with a typed request and reply.
+    fn call<Req: Message, Reply: Message>(
+        &self,
+        service: &str,
+        method: &str,
+        req: &Req,
+        timeout: Option<Duration>,
+    ) -> Result<Reply> {
+        let payload = req.write_to_bytes().map_err(encode_error)?;
+
+        let reply = self.request(service, method, payload, timeout)?;
+
+        Reply::parse_from_bytes(&reply).map_err(decode_error)
+    }
+
+    /// Call the `SayHello` RPC, returning the server's greeting.
+    pub fn say_hello(&self, name: &str) -> Result<String> {
+        self.say_hello_with_timeout(name, None)
+    }
+
+    /// Call the `SayHello` RPC with a deadline that overrides the client
+    /// default.
+    pub fn say_hello_with_timeout(&self, name: &str, timeout: Option<Duration>) -> Result<String> {
+        let mut req = HelloRequest::default();
+
+        req.set_name(name.to_owned());
+
+        info!(sl!(), "sending request to server";
+            "request" => name);
+
+        let reply: HelloReply = self.call(MY_SERVICE_NAME, "SayHello", &req, timeout)?;
+
+        info!(sl!(), "response received";
+            "response" => reply.get_message());
+
+        Ok(reply.get_message().to_string())
+    }
+
+    /// Call the `Shutdown` RPC, returning the server's reply message.
+    pub fn shutdown(&self) -> Result<String> {
+        self.shutdown_with_timeout(None)
+    }
+
+    /// Call the `Shutdown` RPC with a deadline that overrides the client
+    /// default.
+    pub fn shutdown_with_timeout(&self, timeout: Option<Duration>) -> Result<String> {
+        self.shutdown_with_params(&ShutdownParams::default(), timeout)
+    }
+
+    /// Call the `Shutdown` RPC, giving the server a reason, how long to let
+    /// in-flight requests finish and whether to skip waiting for them. A
+    /// zero grace period means the server's default.
+    pub fn shutdown_with_params(
+        &self,
+        params: &ShutdownParams,
+        timeout: Option<Duration>,
+    ) -> Result<String> {
+        let mut req = ShutdownRequest::default();
+
+        req.set_reason(params.reason.clone());
+        req.set_force(params.force);
+
+        if let Some(grace_period) = params.grace_period {
+            req.set_grace_period_ms(u64::try_from(grace_period.as_millis()).unwrap_or(u64::MAX));
+        }
+
+        let reply: ShutdownReply = self.call(MY_SERVICE_NAME, "Shutdown", &req, timeout)?;
+
+        info!(sl!(), "response received";
+            "response" => format!("{:?}", reply));
+
+        Ok(reply.get_message().to_string())
+    }
+
+    /// Call the `Health.Check` RPC, returning the status of `service` (""
+    /// for the server as a whole).
+    pub fn health_check(&self, service: &str, timeout: Option<Duration>) -> Result<ServingStatus> {
+        let mut req = HealthCheckRequest::default();
+
+        req.set_service(service.to_string());
+
+        let reply: HealthCheckResponse = self.call(HEALTH_SERVICE_NAME, "Check", &req, timeout)?;
+
+        info!(sl!(), "response received";
+            "service" => service,
+            "status" => format!("{:?}", reply.get_status()));
+
+        Ok(reply.get_status())
+    }
+
+    /// Call the `Health.Watch` RPC, returning once the status of `service`
+    /// differs from `last_status`, or `wait` has passed (`None` means the
+    /// server's default), whichever is sooner.
+    pub fn health_watch(
+        &self,
+        service: &str,
+        last_status: ServingStatus,
+        wait: Option<Duration>,
+        timeout: Option<Duration>,
+    ) -> Result<ServingStatus> {
+        let mut req = HealthWatchRequest::default();
+
+        req.set_service(service.to_string());
+        req.set_last_status(last_status);
+
+        if let Some(wait) = wait {
+            req.set_wait_ms(u64::try_from(wait.as_millis()).unwrap_or(u64::MAX));
+        }
+
+        let reply: HealthCheckResponse = self.call(HEALTH_SERVICE_NAME, "Watch", &req, timeout)?;
+
+        info!(sl!(), "response received";
+            "service" => service,
+            "status" => format!("{:?}", reply.get_status()));
+
+        Ok(reply.get_status())
+    }
+
+    /// Call the `ServerReflection.ListServices` RPC, returning the services
+    /// the server has registered.
+    pub fn list_services(&self, timeout: Option<Duration>) -> Result<Vec<ServiceInfo>> {
+        let req = ListServicesRequest::default();
+
+        let mut reply: ListServicesResponse =
+            self.call(REFLECTION_SERVICE_NAME, "ListServices", &req, timeout)?;
+
+        Ok(reply.take_services().into_vec())
+    }
+
+    /// Call the `ServerReflection.FileContainingSymbol` RPC, returning the
+    /// serialized `FileDescriptorProto` of the file defining `symbol` (a
+    /// fully qualified service, method or message name).
+    pub fn file_containing_symbol(
+        &self,
+        symbol: &str,
+        timeout: Option<Duration>,
+    ) -> Result<Vec<u8>> {
+        let mut req = FileContainingSymbolRequest::default();
+
+        req.set_symbol(symbol.to_string());
+
+        let mut reply: FileDescriptorResponse = self.call(
+            REFLECTION_SERVICE_NAME,
+            "FileContainingSymbol",
+            &req,
+            timeout,
+        )?;
+
+        Ok(reply.take_file_descriptor_proto())
+    }
+
+    /// Call any method described by the client's descriptors, given as
+    /// `package.Service/Method`, with a JSON request. Returns the reply
+    /// as JSON.
+    pub fn call_json(
+        &self,
+        method: &str,
+        request: &str,
+        timeout: Option<Duration>,
+    ) -> Result<serde_json::Value> {
+        let (info, payload) = encode_json_request(&self.descriptors, method, request)?;
+
+        let reply = self.request(&info.service, &info.method, payload, timeout)?;
+
+        decode_json_reply(&self.descriptors, &info, &reply)
+    }
+
+    /// Run each command in turn, stopping early after a `Shutdown`.
+    pub fn run_commands(&self, commands: &[&str]) -> Result<()> {
+        for cmd in commands {
+            let (result, shutdown) = handle_cmd(self, cmd);
+            if result.is_err() {
+                return result;
+            }
+
+            if shutdown {
+                break;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Prompt for commands on stdin until the user quits.
+    pub fn run_interactive(&self) -> Result<()> {
+        interactive_client_loop(self)
+    }
+}
+
+// Serialize the JSON "request" for "method" (as "package.Service/Method"),
+// returning the method along with the request.
+pub(crate) fn encode_json_request(
+    descriptors: &DescriptorPool,
+    method: &str,
+    request: &str,
+) -> Result<(MethodInfo, Vec<u8>)> {
+    let info = descriptors.method(method)?;
+
+    let request = match request.trim() {
+        "" => serde_json::Value::Object(serde_json::Map::new()),
+        r => match serde_json::from_str(r) {
+            Ok(v) => v,
+            Err(e) => return Err(Error::Usage(format!("invalid JSON request {:?}: {}", r, e))),
+        },
+    };
+
+    let payload = descriptors.encode(&info.input_type, &request)?;
+
+    info!(sl!(), "sending request to server";
+        "service" => info.service.clone(),
+        "method" => info.method.clone(),
+        "request" => request.to_string());
+
+    Ok((info, payload))
+}
+
+// Decode the serialized reply to a call of "info" as JSON.
+pub(crate) fn decode_json_reply(
+    descriptors: &DescriptorPool,
+    info: &MethodInfo,
+    reply: &[u8],
+) -> Result<serde_json::Value> {
+    let reply = descriptors.decode(&info.output_type, reply)?;
+
+    info!(sl!(), "response received";
+        "response" => reply.to_string());
+
+    Ok(reply)
+}
+
+// Split a command name of the form "Name[@duration]".
+fn parse_cmd_name(field: &str) -> Result<(&str, Option<Duration>)> {
+    let mut parts = field.splitn(2, TIMEOUT_SEPARATOR);
+
+    let name = parts.next().unwrap_or_default();
+
+    let timeout = match parts.next() {
+        Some(t) => Some(parse_duration(t)?),
+        None => None,
+    };
+
+    Ok((name, timeout))
+}
+
+// Split a command line into the command name, its deadline (if any) and
+// everything after the name, with its whitespace intact (a JSON request may
+// contain significant spaces).
+pub(crate) fn parse_cmd_line(line: &str) -> Result<(&str, Option<Duration>, &str)> {
+    let field = match line.split_whitespace().next() {
+        Some(f) => f,
+        None => return Err(Error::Usage("empty command".to_string())),
+    };
+
+    let (name, timeout) = parse_cmd_name(field)?;
+
+    let args = line
+        .trim()
+        .split_once(char::is_whitespace)
+        .map(|(_, args)| args.trim())
+        .unwrap_or_default();
+
+    Ok((name, timeout, args))
 }
 
 // Execute the ttRPC specified by the first field of "line". Return a result
 // along with a bool which if set means the client should shutdown.
-fn handle_cmd(cfg: &Config, client: &MyServiceClient, line: &str) -> (Result<()>, bool) {
-    let fields: Vec<&str> = line.split_whitespace().collect();
-    let name = fields[0];
+fn handle_cmd(client: &HelloClient, line: &str) -> (Result<()>, bool) {
+    let (name, timeout, args) = match parse_cmd_line(line) {
+        Ok(r) => r,
+        Err(e) => return (Err(e), false),
+    };
 
     let f = match get_cmd_func(&name) {
         Ok(fp) => fp,
         Err(e) => return (Err(e), false),
     };
 
-    let args = if fields.len() > 1 {
-        fields[1..].join(" ")
-    } else {
-        String::new()
-    };
+    let id = command_request_id(&client.metadata());
+
+    let result = client.with_request_id(&id, || {
+        let result = f(client, timeout, args);
+
+        if result.is_ok() {
+            info!(sl!(), "Command {:} returned {:?}", name, result);
+        }
+
+        result
+    });
 
-    let result = f(cfg, client, &args);
     if result.is_err() {
         return (result, false);
     }
 
-    info!(sl!(), "Command {:} returned {:?}", name, result);
-
     let shutdown = name == SHUTDOWN_CMD;
 
     (Ok(()), shutdown)
 }
 
-fn interactive_client_loop(cfg: &Config, client: &MyServiceClient) -> Result<()> {
-    let names = get_cmd_names();
-    let quit = "quit";
+// Print the commands an interactive client accepts, and how to use them.
+pub(crate) fn print_commands(quit: &str) {
+    println!("Commands ('{}' to end):\n", quit);
 
-    loop {
-        println!("Commands ('{}' to end):\n", quit);
+    get_cmd_names().iter().for_each(|n| println!(" - {}", n));
+
+    println!(
+        "\n(Append '{}duration' to a command to set its deadline, for example 'SayHello{}500ms')",
+        TIMEOUT_SEPARATOR, TIMEOUT_SEPARATOR
+    );
+
+    println!(
+        "({} takes '[{}] [{}duration] [reason]')",
+        SHUTDOWN_CMD, SHUTDOWN_FORCE_ARG, SHUTDOWN_GRACE_PREFIX
+    );
+
+    println!(
+        "({} takes 'key{}value' to send with later requests, or 'key{}' to stop sending it)",
+        SET_HEADER_CMD, METADATA_SEPARATOR, METADATA_SEPARATOR
+    );
+
+    println!();
+}
 
-        names.iter().for_each(|n| println!(" - {}", n));
+fn interactive_client_loop(client: &HelloClient) -> Result<()> {
+    let quit = "quit";
 
-        println!();
+    loop {
+        print_commands(quit);
 
         let line = readline("Enter command").expect("failed to read line");
 
-        if line == "" || line == "\n" {
+        if line.trim().is_empty() {
             continue;
         }
 
@@ -318,7 +1098,7 @@ fn interactive_client_loop(cfg: &Config, client: &MyServiceClient) -> Result<()>
             break;
         }
 
-        let (result, shutdown) = handle_cmd(cfg, client, &line);
+        let (result, shutdown) = handle_cmd(client, &line);
         if result.is_err() {
             return result;
         }
@@ -331,7 +1111,7 @@ fn interactive_client_loop(cfg: &Config, client: &MyServiceClient) -> Result<()>
     Ok(())
 }
 
-fn readline(prompt: &str) -> std::result::Result<String, String> {
+pub(crate) fn readline(prompt: &str) -> std::result::Result<String, String> {
     print!("{}: ", prompt);
 
     match io::stdout().flush() {
@@ -349,30 +1129,396 @@ fn readline(prompt: &str) -> std::result::Result<String, String> {
     }
 }
 
-fn cmd_say_hello(_cfg: &Config, client: &MyServiceClient, msg: &str) -> Result<()> {
-    let mut req = HelloRequest::default();
+fn cmd_say_hello(client: &HelloClient, timeout: Option<Duration>, msg: &str) -> Result<()> {
+    client.say_hello_with_timeout(msg, timeout)?;
 
-    req.set_name(msg.to_owned());
+    Ok(())
+}
 
-    info!(sl!(), "sending request to server";
-        "request" => msg);
+// Fail unless "status" shows "service" is serving.
+pub(crate) fn check_serving(service: &str, status: ServingStatus) -> Result<()> {
+    match status {
+        ServingStatus::SERVING => Ok(()),
+        status => Err(Error::Unhealthy {
+            service: service.to_string(),
+            status: format!("{:?}", status),
+        }),
+    }
+}
 
-    let reply = client.say_hello(&req, TIMEOUT_NANO).expect("rpc");
-    info!(sl!(), "response received";
-        "response" => reply.get_message());
+fn cmd_health(client: &HelloClient, timeout: Option<Duration>, service: &str) -> Result<()> {
+    let status = client.health_check(service, timeout)?;
+
+    check_serving(service, status)
+}
+
+// Wait for the status of the service to change from its current status,
+// failing unless it changes to serving.
+fn cmd_health_watch(client: &HelloClient, timeout: Option<Duration>, service: &str) -> Result<()> {
+    let last_status = match client.health_check(service, timeout) {
+        Ok(status) => status,
+        Err(Error::RpcStatus {
+            code: Code::NOT_FOUND,
+            ..
+        }) => ServingStatus::SERVICE_UNKNOWN,
+        Err(e) => return Err(e),
+    };
+
+    // Have the server reply well before the deadline.
+    let wait = timeout.or(client.timeout).map(|t| t / 2);
+
+    loop {
+        let status = client.health_watch(service, last_status, wait, timeout)?;
+
+        if status != last_status {
+            return check_serving(service, status);
+        }
+    }
+}
+
+fn cmd_list_services(client: &HelloClient, timeout: Option<Duration>, _args: &str) -> Result<()> {
+    for service in client.list_services(timeout)? {
+        info!(sl!(), "service";
+            "name" => service.get_name(),
+            "methods" => service.get_methods().join(" "),
+            "file" => service.get_file());
+    }
 
     Ok(())
 }
 
-fn cmd_shutdown(_cfg: &Config, client: &MyServiceClient, _msg: &str) -> Result<()> {
-    info!(sl!(), "cmd_shutdown: requesting shutdown");
+// Call a method by name: "<package.Service/Method> [<json>]". The reply is
+// printed as JSON.
+fn cmd_call(client: &HelloClient, timeout: Option<Duration>, args: &str) -> Result<()> {
+    let (method, request) = parse_call_args(args)?;
 
-    let req = ShutdownRequest::default();
+    let reply = client.call_json(method, request, timeout)?;
 
-    let reply = client.shutdown(&req, TIMEOUT_NANO).expect("rpc");
+    print_json_reply(&reply)
+}
 
-    info!(sl!(), "response received";
-        "response" => format!("{:?}", reply));
+// Split the Call command arguments into the method and the JSON request.
+pub(crate) fn parse_call_args(args: &str) -> Result<(&str, &str)> {
+    let mut parts = args.splitn(2, char::is_whitespace);
+
+    match parts.next() {
+        Some(method) if !method.is_empty() => Ok((method, parts.next().unwrap_or_default())),
+        _ => Err(Error::Usage(
+            "Call needs a method ('package.Service/Method') and optional JSON request".to_string(),
+        )),
+    }
+}
+
+pub(crate) fn print_json_reply(reply: &serde_json::Value) -> Result<()> {
+    match serde_json::to_string_pretty(reply) {
+        Ok(s) => println!("{}", s),
+        Err(e) => return Err(Error::Usage(format!("failed to format reply: {}", e))),
+    }
 
     Ok(())
 }
+
+/// Split a metadata argument of the form `key=value`.
+pub fn parse_metadata_arg(arg: &str) -> Result<(&str, &str)> {
+    match arg.trim().split_once(METADATA_SEPARATOR) {
+        Some((key, value)) if !key.is_empty() => Ok((key, value)),
+        _ => Err(Error::Usage(format!(
+            "invalid metadata {:?} (expected 'key{}value')",
+            arg, METADATA_SEPARATOR
+        ))),
+    }
+}
+
+fn cmd_set_header(client: &HelloClient, _timeout: Option<Duration>, args: &str) -> Result<()> {
+    let (key, value) = parse_metadata_arg(args)?;
+
+    match value {
+        "" => client.remove_metadata(key),
+        value => client.set_metadata(key, value),
+    }
+
+    info!(sl!(), "request metadata";
+        "metadata" => format!("{:?}", client.metadata()));
+
+    Ok(())
+}
+
+// Parse the Shutdown command arguments: "[force] [grace=<duration>]
+// [reason...]".
+pub(crate) fn parse_shutdown_args(args: &str) -> Result<ShutdownParams> {
+    let mut params = ShutdownParams::default();
+
+    let mut words = args.split_whitespace().peekable();
+
+    while let Some(word) = words.peek() {
+        if *word == SHUTDOWN_FORCE_ARG {
+            params.force = true;
+        } else if let Some(grace) = word.strip_prefix(SHUTDOWN_GRACE_PREFIX) {
+            params.grace_period = Some(parse_duration(grace)?);
+        } else {
+            break;
+        }
+
+        words.next();
+    }
+
+    params.reason = words.collect::<Vec<&str>>().join(" ");
+
+    Ok(params)
+}
+
+fn cmd_shutdown(client: &HelloClient, timeout: Option<Duration>, args: &str) -> Result<()> {
+    let params = parse_shutdown_args(args)?;
+
+    info!(sl!(), "cmd_shutdown: requesting shutdown";
+        "params" => format!("{:?}", params));
+
+    let msg = client.shutdown_with_params(&params, timeout)?;
+
+    info!(sl!(), "cmd_shutdown: server shutting down"; "message" => msg);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::server::server_create_abstract_fds;
+    use std::io::{BufRead, BufReader};
+    use std::os::unix::io::FromRawFd;
+    use std::os::unix::net::UnixListener;
+    use std::process;
+    use tempfile::TempDir;
+
+    const NAMINGS: &[AbstractNaming] = &[
+        AbstractNaming::TtrpcNul,
+        AbstractNaming::Exact,
+        AbstractNaming::Auto,
+    ];
+
+    fn close_fds(fds: &[RawFd]) {
+        for fd in fds {
+            let _ = close(*fd);
+        }
+    }
+
+    // Whether a client using "client" naming can reach a server bound with
+    // "server" naming.
+    fn abstract_naming_connects(server: AbstractNaming, client: AbstractNaming) -> bool {
+        // Abstract names are global to the network namespace, so make them
+        // unique to this test run.
+        let name = format!(
+            "test-protobuf-ttrpc-{}-{}-{}",
+            process::id(),
+            server,
+            client
+        );
+
+        let server_fds = server_create_abstract_fds(&name, server).unwrap();
+
+        let result = client_create_abstract_fd(&name, client);
+
+        close_fds(&server_fds);
+
+        match result {
+            Ok(fd) => {
+                close_fds(&[fd]);
+                true
+            }
+            Err(Error::Connect(_)) => false,
+            Err(e) => panic!(
+                "unexpected error connecting {} to {}: {:?}",
+                client, server, e
+            ),
+        }
+    }
+
+    #[test]
+    fn test_abstract_naming_matrix() {
+        // (server naming, client naming, expect connection)
+        let tests = vec![
+            (AbstractNaming::TtrpcNul, AbstractNaming::TtrpcNul, true),
+            (AbstractNaming::TtrpcNul, AbstractNaming::Exact, false),
+            (AbstractNaming::TtrpcNul, AbstractNaming::Auto, true),
+            (AbstractNaming::Exact, AbstractNaming::TtrpcNul, false),
+            (AbstractNaming::Exact, AbstractNaming::Exact, true),
+            (AbstractNaming::Exact, AbstractNaming::Auto, true),
+            (AbstractNaming::Auto, AbstractNaming::TtrpcNul, true),
+            (AbstractNaming::Auto, AbstractNaming::Exact, true),
+            (AbstractNaming::Auto, AbstractNaming::Auto, true),
+        ];
+
+        // Every pair is covered.
+        assert_eq!(tests.len(), NAMINGS.len() * NAMINGS.len());
+
+        for (server, client, expected) in tests {
+            assert_eq!(
+                abstract_naming_connects(server, client),
+                expected,
+                "server naming {}, client naming {}",
+                server,
+                client
+            );
+        }
+    }
+
+    #[test]
+    fn test_abstract_naming_auto_binds_both_names() {
+        let name = format!("test-protobuf-ttrpc-{}-auto-binds", process::id());
+
+        let fds = server_create_abstract_fds(&name, AbstractNaming::Auto).unwrap();
+
+        assert_eq!(fds.len(), 2);
+
+        // The names are bound, so binding either again fails.
+        for naming in &[AbstractNaming::TtrpcNul, AbstractNaming::Exact] {
+            assert!(
+                server_create_abstract_fds(&name, *naming).is_err(),
+                "{} name not bound",
+                naming
+            );
+        }
+
+        close_fds(&fds);
+    }
+
+    // Sent by the fake VMM after its reply, standing in for the first ttRPC
+    // data from the guest.
+    const HVSOCK_TEST_DATA: &[u8] = b"\x00\x00\x00\x05ttrpc";
+
+    // Connect to "port" via a fake VMM listening on a hybrid vsock socket.
+    // The VMM reads the client's request line, sends back whatever
+    // "respond" returns for it (if anything) followed by HVSOCK_TEST_DATA,
+    // then waits for the client to hang up. Returns the outcome of the
+    // handshake and the line the client sent.
+    fn hvsock_handshake<F>(port: u32, respond: F) -> (Result<()>, String)
+    where
+        F: FnOnce(&str) -> Option<String> + Send + 'static,
+    {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("hvsock.sock");
+
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let vmm = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+
+            let mut line = String::new();
+
+            BufReader::new(stream.try_clone().unwrap())
+                .read_line(&mut line)
+                .unwrap();
+
+            if let Some(reply) = respond(&line) {
+                stream.write_all(reply.as_bytes()).unwrap();
+
+                // The client hangs up straight away if it rejects the reply.
+                let _ = stream.write_all(HVSOCK_TEST_DATA);
+            }
+
+            let _ = stream.read_to_end(&mut Vec::new());
+
+            line
+        });
+
+        let result = client_create_hvsock_fd(path.to_str().unwrap(), port).map(|fd| {
+            let mut stream = unsafe { UnixStream::from_raw_fd(fd) };
+
+            let mut data = vec![0u8; HVSOCK_TEST_DATA.len()];
+
+            stream.read_exact(&mut data).unwrap();
+
+            assert_eq!(data, HVSOCK_TEST_DATA, "handshake consumed ttRPC data");
+        });
+
+        (result, vmm.join().unwrap())
+    }
+
+    fn assert_hvsock_error(result: Result<()>, needle: &str) {
+        match result {
+            Err(Error::Vsock(msg)) => assert!(
+                msg.contains(needle),
+                "error does not mention {:?}: {}",
+                needle,
+                msg
+            ),
+            r => panic!("expected vsock error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_hvsock_handshake() {
+        let (result, line) = hvsock_handshake(1024, |_| Some("OK 1073741824\n".to_string()));
+
+        result.unwrap();
+
+        assert_eq!(line, "CONNECT 1024\n");
+    }
+
+    #[test]
+    fn test_hvsock_handshake_malformed_reply() {
+        let replies = vec![
+            "\n",
+            "OK\n",
+            "OK \n",
+            "OK x\n",
+            "OK -1\n",
+            "OK 4294967296\n",
+            "OK 1 2\n",
+            // Longer than any valid reply, so is cut short.
+            "OK 00000000000000000000000000000001\n",
+        ];
+
+        for reply in replies {
+            let (result, _) = hvsock_handshake(1024, move |_| Some(reply.to_string()));
+
+            assert_hvsock_error(result, "unexpected hybrid vsock reply");
+        }
+    }
+
+    #[test]
+    fn test_hvsock_handshake_refused() {
+        let replies = vec!["FAILURE\n", "ERR no listener on port 1024\n", "ok 5\n"];
+
+        for reply in replies {
+            let (result, _) = hvsock_handshake(1024, move |_| Some(reply.to_string()));
+
+            assert_hvsock_error(result, "unexpected hybrid vsock reply");
+        }
+    }
+
+    #[test]
+    fn test_hvsock_handshake_silent_peer() {
+        let start = Instant::now();
+
+        let (result, line) = hvsock_handshake(1024, |_| None);
+
+        assert!(start.elapsed() >= HVSOCK_HANDSHAKE_TIMEOUT);
+
+        assert_hvsock_error(result, "handshake");
+
+        assert_eq!(line, "CONNECT 1024\n");
+    }
+
+    #[test]
+    fn test_parse_cmd_line() {
+        let (name, timeout, args) = parse_cmd_line("  SayHello@2s  foo  bar ").unwrap();
+
+        assert_eq!(name, "SayHello");
+        assert_eq!(timeout, Some(Duration::from_secs(2)));
+        assert_eq!(args, "foo  bar");
+
+        let (name, timeout, args) = parse_cmd_line("Shutdown").unwrap();
+
+        assert_eq!(name, "Shutdown");
+        assert_eq!(timeout, None);
+        assert_eq!(args, "");
+
+        for line in &["", " ", "\t\n"] {
+            match parse_cmd_line(line) {
+                Err(Error::Usage(_)) => (),
+                r => panic!("expected usage error for {:?}, got {:?}", line, r),
+            }
+        }
+    }
+}
@@ -0,0 +1,1319 @@
+// Description: Encode and decode messages as JSON using protobuf descriptors
+
+use crate::error::{Error, Result};
+use crate::{health, reflection, service};
+use protobuf::descriptor::{
+    DescriptorProto, EnumDescriptorProto, FieldDescriptorProto, FieldDescriptorProto_Label,
+    FieldDescriptorProto_Type, FileDescriptorProto, FileDescriptorSet,
+};
+use protobuf::wire_format::WireType;
+use protobuf::{CodedInputStream, CodedOutputStream, Message, ProtobufError};
+use serde_json::{Map, Number, Value};
+
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fs;
+use std::path::Path;
+
+type Type = FieldDescriptorProto_Type;
+
+pub(crate) fn encode_error(e: ProtobufError) -> Error {
+    Error::Usage(format!("failed to encode request: {:?}", e))
+}
+
+pub(crate) fn decode_error(e: ProtobufError) -> Error {
+    Error::Transport(format!("failed to decode reply: {:?}", e))
+}
+
+// Decode bytes field text, which the protobuf JSON mapping allows to be
+// standard or URL-safe base64, with or without padding.
+fn base64_decode(text: &str) -> std::result::Result<Vec<u8>, base64::DecodeError> {
+    let config = match text.contains(['-', '_']) {
+        true => base64::URL_SAFE_NO_PAD,
+        false => base64::STANDARD_NO_PAD,
+    };
+
+    base64::decode_config(text.trim_end_matches('='), config)
+}
+
+// The JSON name of a field: "json_name" if protoc set it, else the field
+// name converted to lowerCamelCase.
+fn json_name(field: &FieldDescriptorProto) -> String {
+    if field.has_json_name() {
+        return field.get_json_name().to_string();
+    }
+
+    let mut name = String::new();
+    let mut upper = false;
+
+    for c in field.get_name().chars() {
+        match (c, upper) {
+            ('_', _) => upper = true,
+            (c, true) => {
+                name.extend(c.to_uppercase());
+                upper = false;
+            }
+            (c, false) => name.push(c),
+        }
+    }
+
+    name
+}
+
+fn is_repeated(field: &FieldDescriptorProto) -> bool {
+    field.get_label() == FieldDescriptorProto_Label::LABEL_REPEATED
+}
+
+// Scalar types that proto3 encodes packed when repeated.
+fn is_packable(t: Type) -> bool {
+    !matches!(
+        t,
+        Type::TYPE_STRING | Type::TYPE_BYTES | Type::TYPE_MESSAGE | Type::TYPE_GROUP
+    )
+}
+
+fn wire_type(t: Type) -> WireType {
+    match t {
+        Type::TYPE_DOUBLE | Type::TYPE_FIXED64 | Type::TYPE_SFIXED64 => WireType::WireTypeFixed64,
+        Type::TYPE_FLOAT | Type::TYPE_FIXED32 | Type::TYPE_SFIXED32 => WireType::WireTypeFixed32,
+        Type::TYPE_STRING | Type::TYPE_BYTES | Type::TYPE_MESSAGE => {
+            WireType::WireTypeLengthDelimited
+        }
+        Type::TYPE_GROUP => WireType::WireTypeStartGroup,
+        _ => WireType::WireTypeVarint,
+    }
+}
+
+fn json_f64(v: &Value) -> Option<f64> {
+    match v {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => match s.as_str() {
+            "NaN" => Some(f64::NAN),
+            "Infinity" => Some(f64::INFINITY),
+            "-Infinity" => Some(f64::NEG_INFINITY),
+            s => s.parse().ok(),
+        },
+        _ => None,
+    }
+}
+
+fn f64_json(f: f64) -> Value {
+    match Number::from_f64(f) {
+        Some(n) => Value::Number(n),
+        None if f.is_nan() => Value::String("NaN".to_string()),
+        None if f > 0.0 => Value::String("Infinity".to_string()),
+        None => Value::String("-Infinity".to_string()),
+    }
+}
+
+// Integers may be JSON numbers or, as the protobuf JSON mapping uses for
+// 64-bit values, strings.
+fn json_i64(v: &Value) -> Option<i64> {
+    match v {
+        Value::Number(n) => n.as_i64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+fn json_u64(v: &Value) -> Option<u64> {
+    match v {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s.parse().ok(),
+        _ => None,
+    }
+}
+
+/// How to call a method found in a [`DescriptorPool`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct MethodInfo {
+    /// Fully qualified service name, for example "service.MyService".
+    pub service: String,
+
+    /// Method name, for example "SayHello".
+    pub method: String,
+
+    /// Fully qualified request message type, with a leading ".".
+    pub input_type: String,
+
+    /// Fully qualified reply message type, with a leading ".".
+    pub output_type: String,
+}
+
+/// A set of protobuf file descriptors used to convert messages to and
+/// from JSON (using the protobuf JSON mapping) without generated code.
+///
+/// Groups and the special JSON forms of the well-known types (such as
+/// `google.protobuf.Timestamp`) are not supported.
+#[derive(Debug, Clone, Default)]
+pub struct DescriptorPool {
+    files: Vec<FileDescriptorProto>,
+    messages: HashMap<String, DescriptorProto>,
+    enums: HashMap<String, EnumDescriptorProto>,
+}
+
+impl DescriptorPool {
+    pub fn new() -> Self {
+        DescriptorPool::default()
+    }
+
+    /// A pool containing the descriptors embedded in this crate, for
+    /// `MyService`, `Health` and `ServerReflection`.
+    pub fn embedded() -> Self {
+        let mut pool = DescriptorPool::new();
+
+        pool.add_file(service::file_descriptor_proto().clone());
+        pool.add_file(health::file_descriptor_proto().clone());
+        pool.add_file(reflection::file_descriptor_proto().clone());
+
+        pool
+    }
+
+    fn add_message(&mut self, scope: &str, msg: &DescriptorProto) {
+        let name = format!("{}.{}", scope, msg.get_name());
+
+        for e in msg.get_enum_type() {
+            self.enums
+                .insert(format!("{}.{}", name, e.get_name()), e.clone());
+        }
+
+        for nested in msg.get_nested_type() {
+            self.add_message(&name, nested);
+        }
+
+        self.messages.insert(name, msg.clone());
+    }
+
+    /// Add the types and services defined by `file`.
+    pub fn add_file(&mut self, file: FileDescriptorProto) {
+        let scope = match file.get_package() {
+            "" => "".to_string(),
+            p => format!(".{}", p),
+        };
+
+        for msg in file.get_message_type() {
+            self.add_message(&scope, msg);
+        }
+
+        for e in file.get_enum_type() {
+            self.enums
+                .insert(format!("{}.{}", scope, e.get_name()), e.clone());
+        }
+
+        self.files.push(file);
+    }
+
+    /// Add every file in a serialized `FileDescriptorSet`, as written by
+    /// `protoc --include_imports --descriptor_set_out=<path>`.
+    pub fn add_descriptor_set<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let path = path.as_ref();
+
+        let bytes = match fs::read(path) {
+            Ok(b) => b,
+            Err(e) => {
+                return Err(Error::Io(format!(
+                    "failed to read descriptor set {:?}: {}",
+                    path, e
+                )))
+            }
+        };
+
+        let mut set = FileDescriptorSet::new();
+
+        if let Err(e) = set.merge_from_bytes(&bytes) {
+            return Err(Error::Usage(format!(
+                "invalid descriptor set {:?}: {:?}",
+                path, e
+            )));
+        }
+
+        for file in set.get_file() {
+            self.add_file(file.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Find a method by name, given as `package.Service/Method` (as in the
+    /// ttRPC request path, with or without the leading `/`) or
+    /// `package.Service.Method`.
+    pub fn method(&self, name: &str) -> Result<MethodInfo> {
+        let name = name.trim_start_matches('/');
+
+        let split = match name.rfind(['/', '.']) {
+            Some(i) => i,
+            None => {
+                return Err(Error::Usage(format!(
+                    "invalid method {:?} (expected 'package.Service/Method')",
+                    name
+                )))
+            }
+        };
+
+        let (service_name, method_name) = (&name[..split], &name[split + 1..]);
+
+        for file in &self.files {
+            for svc in file.get_service() {
+                let full_name = match file.get_package() {
+                    "" => svc.get_name().to_string(),
+                    p => format!("{}.{}", p, svc.get_name()),
+                };
+
+                if full_name != service_name {
+                    continue;
+                }
+
+                if let Some(m) = svc
+                    .get_method()
+                    .iter()
+                    .find(|m| m.get_name() == method_name)
+                {
+                    return Ok(MethodInfo {
+                        service: full_name,
+                        method: method_name.to_string(),
+                        input_type: m.get_input_type().to_string(),
+                        output_type: m.get_output_type().to_string(),
+                    });
+                }
+            }
+        }
+
+        Err(Error::Usage(format!(
+            "unknown method {:?} (use a descriptor set to describe other services)",
+            name
+        )))
+    }
+
+    fn message(&self, type_name: &str) -> Result<&DescriptorProto> {
+        match self.messages.get(type_name) {
+            Some(m) => Ok(m),
+            None => Err(Error::Usage(format!(
+                "unknown message type {:?}",
+                type_name
+            ))),
+        }
+    }
+
+    // The key and value fields of "field" if it is a map.
+    fn map_entry(
+        &self,
+        field: &FieldDescriptorProto,
+    ) -> Option<(&FieldDescriptorProto, &FieldDescriptorProto)> {
+        if !is_repeated(field) || field.get_field_type() != Type::TYPE_MESSAGE {
+            return None;
+        }
+
+        let entry = self.messages.get(field.get_type_name())?;
+
+        if !entry.get_options().get_map_entry() {
+            return None;
+        }
+
+        let key = entry.get_field().iter().find(|f| f.get_number() == 1)?;
+        let value = entry.get_field().iter().find(|f| f.get_number() == 2)?;
+
+        Some((key, value))
+    }
+
+    fn enum_number(&self, field: &FieldDescriptorProto, v: &Value) -> Option<i32> {
+        match v {
+            Value::String(s) => self
+                .enums
+                .get(field.get_type_name())?
+                .get_value()
+                .iter()
+                .find(|ev| ev.get_name() == s)
+                .map(|ev| ev.get_number()),
+            v => json_i64(v).and_then(|n| i32::try_from(n).ok()),
+        }
+    }
+
+    fn enum_json(&self, field: &FieldDescriptorProto, n: i32) -> Value {
+        let name = self
+            .enums
+            .get(field.get_type_name())
+            .and_then(|e| e.get_value().iter().find(|ev| ev.get_number() == n))
+            .map(|ev| ev.get_name().to_string());
+
+        match name {
+            Some(name) => Value::String(name),
+            None => Value::from(n),
+        }
+    }
+
+    /// Encode `json` as a message of type `type_name` (fully qualified,
+    /// with a leading ".").
+    pub fn encode(&self, type_name: &str, json: &Value) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+
+        {
+            let mut os = CodedOutputStream::vec(&mut buf);
+
+            self.encode_message(type_name, json, &mut os)?;
+
+            os.flush().map_err(encode_error)?;
+        }
+
+        Ok(buf)
+    }
+
+    fn encode_message(
+        &self,
+        type_name: &str,
+        json: &Value,
+        os: &mut CodedOutputStream,
+    ) -> Result<()> {
+        let msg = self.message(type_name)?;
+
+        let obj = match json.as_object() {
+            Some(o) => o,
+            None => {
+                return Err(Error::Usage(format!(
+                    "expected a JSON object for {}, got {}",
+                    type_name, json
+                )))
+            }
+        };
+
+        for (key, v) in obj {
+            let field = msg
+                .get_field()
+                .iter()
+                .find(|f| f.get_name() == key || json_name(f) == *key);
+
+            let field = match field {
+                Some(f) => f,
+                None => {
+                    return Err(Error::Usage(format!(
+                        "{} has no field {:?}",
+                        type_name, key
+                    )))
+                }
+            };
+
+            if v.is_null() {
+                continue;
+            }
+
+            self.encode_field(field, v, os)?;
+        }
+
+        Ok(())
+    }
+
+    fn encode_field(
+        &self,
+        field: &FieldDescriptorProto,
+        v: &Value,
+        os: &mut CodedOutputStream,
+    ) -> Result<()> {
+        if !is_repeated(field) {
+            return self.encode_single(field, v, os);
+        }
+
+        let number = field.get_number() as u32;
+
+        if let Some((key_field, value_field)) = self.map_entry(field) {
+            let obj = match v.as_object() {
+                Some(o) => o,
+                None => {
+                    return Err(Error::Usage(format!(
+                        "expected a JSON object for map field {:?}, got {}",
+                        field.get_name(),
+                        v
+                    )))
+                }
+            };
+
+            for (key, value) in obj {
+                // JSON map keys are always strings.
+                let key = match (key_field.get_field_type(), key.as_str()) {
+                    (Type::TYPE_BOOL, "true") => Value::Bool(true),
+                    (Type::TYPE_BOOL, "false") => Value::Bool(false),
+                    (Type::TYPE_BOOL, _) => {
+                        return Err(Error::Usage(format!(
+                            "invalid key {:?} for map field {:?} (expected \"true\" or \"false\")",
+                            key,
+                            field.get_name()
+                        )))
+                    }
+                    _ => Value::String(key.clone()),
+                };
+
+                let mut entry = Vec::new();
+
+                {
+                    let mut es = CodedOutputStream::vec(&mut entry);
+
+                    self.encode_single(key_field, &key, &mut es)?;
+                    self.encode_single(value_field, value, &mut es)?;
+
+                    es.flush().map_err(encode_error)?;
+                }
+
+                os.write_bytes(number, &entry).map_err(encode_error)?;
+            }
+
+            return Ok(());
+        }
+
+        let values = match v.as_array() {
+            Some(a) => a,
+            None => {
+                return Err(Error::Usage(format!(
+                    "expected a JSON array for repeated field {:?}, got {}",
+                    field.get_name(),
+                    v
+                )))
+            }
+        };
+
+        if !is_packable(field.get_field_type()) {
+            for v in values {
+                self.encode_single(field, v, os)?;
+            }
+
+            return Ok(());
+        }
+
+        let mut packed = Vec::new();
+
+        {
+            let mut ps = CodedOutputStream::vec(&mut packed);
+
+            for v in values {
+                self.encode_scalar(field, v, &mut ps)?;
+            }
+
+            ps.flush().map_err(encode_error)?;
+        }
+
+        os.write_bytes(number, &packed).map_err(encode_error)
+    }
+
+    fn encode_single(
+        &self,
+        field: &FieldDescriptorProto,
+        v: &Value,
+        os: &mut CodedOutputStream,
+    ) -> Result<()> {
+        let number = field.get_number() as u32;
+
+        let invalid = || {
+            Error::Usage(format!(
+                "invalid value for field {:?} ({:?}): {}",
+                field.get_name(),
+                field.get_field_type(),
+                v
+            ))
+        };
+
+        match field.get_field_type() {
+            Type::TYPE_MESSAGE => {
+                let mut buf = Vec::new();
+
+                {
+                    let mut ms = CodedOutputStream::vec(&mut buf);
+
+                    self.encode_message(field.get_type_name(), v, &mut ms)?;
+
+                    ms.flush().map_err(encode_error)?;
+                }
+
+                os.write_bytes(number, &buf).map_err(encode_error)
+            }
+            Type::TYPE_STRING => {
+                let s = v.as_str().ok_or_else(invalid)?;
+
+                os.write_string(number, s).map_err(encode_error)
+            }
+            Type::TYPE_BYTES => {
+                let bytes = base64_decode(v.as_str().ok_or_else(invalid)?).map_err(|e| {
+                    Error::Usage(format!(
+                        "invalid base64 for field {:?}: {}: {}",
+                        field.get_name(),
+                        e,
+                        v
+                    ))
+                })?;
+
+                os.write_bytes(number, &bytes).map_err(encode_error)
+            }
+            Type::TYPE_GROUP => Err(Error::Usage(format!(
+                "group field {:?} is not supported",
+                field.get_name()
+            ))),
+            t => {
+                os.write_tag(number, wire_type(t)).map_err(encode_error)?;
+
+                self.encode_scalar(field, v, os)
+            }
+        }
+    }
+
+    // Write a numeric, bool or enum value without a tag.
+    fn encode_scalar(
+        &self,
+        field: &FieldDescriptorProto,
+        v: &Value,
+        os: &mut CodedOutputStream,
+    ) -> Result<()> {
+        let invalid = || {
+            Error::Usage(format!(
+                "invalid value for field {:?} ({:?}): {}",
+                field.get_name(),
+                field.get_field_type(),
+                v
+            ))
+        };
+
+        let out_of_range = || {
+            Error::Usage(format!(
+                "value for field {:?} ({:?}) is out of range: {}",
+                field.get_name(),
+                field.get_field_type(),
+                v
+            ))
+        };
+
+        let i64_value = || json_i64(v).ok_or_else(invalid);
+        let u64_value = || json_u64(v).ok_or_else(invalid);
+        let i32_value = || i32::try_from(i64_value()?).map_err(|_| out_of_range());
+        let u32_value = || u32::try_from(u64_value()?).map_err(|_| out_of_range());
+
+        let f32_value = || {
+            let f = json_f64(v).ok_or_else(invalid)?;
+
+            match f.is_finite() && (f as f32).is_infinite() {
+                true => Err(out_of_range()),
+                false => Ok(f as f32),
+            }
+        };
+
+        let result = match field.get_field_type() {
+            Type::TYPE_DOUBLE => os.write_double_no_tag(json_f64(v).ok_or_else(invalid)?),
+            Type::TYPE_FLOAT => os.write_float_no_tag(f32_value()?),
+            Type::TYPE_INT64 => os.write_int64_no_tag(i64_value()?),
+            Type::TYPE_SINT64 => os.write_sint64_no_tag(i64_value()?),
+            Type::TYPE_SFIXED64 => os.write_sfixed64_no_tag(i64_value()?),
+            Type::TYPE_UINT64 => os.write_uint64_no_tag(u64_value()?),
+            Type::TYPE_FIXED64 => os.write_fixed64_no_tag(u64_value()?),
+            Type::TYPE_INT32 => os.write_int32_no_tag(i32_value()?),
+            Type::TYPE_SINT32 => os.write_sint32_no_tag(i32_value()?),
+            Type::TYPE_SFIXED32 => os.write_sfixed32_no_tag(i32_value()?),
+            Type::TYPE_UINT32 => os.write_uint32_no_tag(u32_value()?),
+            Type::TYPE_FIXED32 => os.write_fixed32_no_tag(u32_value()?),
+            Type::TYPE_BOOL => os.write_bool_no_tag(v.as_bool().ok_or_else(invalid)?),
+            Type::TYPE_ENUM => {
+                os.write_enum_no_tag(self.enum_number(field, v).ok_or_else(invalid)?)
+            }
+            _ => return Err(invalid()),
+        };
+
+        result.map_err(encode_error)
+    }
+
+    /// Decode `bytes`, a message of type `type_name` (fully qualified, with
+    /// a leading "."), as JSON. Unknown fields are ignored.
+    pub fn decode(&self, type_name: &str, bytes: &[u8]) -> Result<Value> {
+        let msg = self.message(type_name)?;
+
+        let mut obj = Map::new();
+
+        let mut is = CodedInputStream::from_bytes(bytes);
+
+        while !is.eof().map_err(decode_error)? {
+            let (number, wt) = is.read_tag_unpack().map_err(decode_error)?;
+
+            let field = match msg
+                .get_field()
+                .iter()
+                .find(|f| f.get_number() as u32 == number)
+            {
+                Some(f) => f,
+                None => {
+                    is.skip_field(wt).map_err(decode_error)?;
+                    continue;
+                }
+            };
+
+            let name = json_name(field);
+
+            if let Some((key_field, value_field)) = self.map_entry(field) {
+                let entry = is.read_bytes().map_err(decode_error)?;
+                let (key, value) = self.decode_map_entry(key_field, value_field, &entry)?;
+
+                if let Value::Object(map) =
+                    obj.entry(name).or_insert_with(|| Value::Object(Map::new()))
+                {
+                    map.insert(key, value);
+                }
+
+                continue;
+            }
+
+            if !is_repeated(field) {
+                obj.insert(name, self.decode_single(field, wt, &mut is)?);
+                continue;
+            }
+
+            let mut values = Vec::new();
+
+            if wt == WireType::WireTypeLengthDelimited && is_packable(field.get_field_type()) {
+                let packed = is.read_bytes().map_err(decode_error)?;
+                let mut ps = CodedInputStream::from_bytes(&packed);
+
+                while !ps.eof().map_err(decode_error)? {
+                    values.push(self.decode_scalar(field, &mut ps)?);
+                }
+            } else {
+                values.push(self.decode_single(field, wt, &mut is)?);
+            }
+
+            if let Value::Array(array) = obj.entry(name).or_insert_with(|| Value::Array(Vec::new()))
+            {
+                array.extend(values);
+            }
+        }
+
+        Ok(Value::Object(obj))
+    }
+
+    fn decode_map_entry(
+        &self,
+        key_field: &FieldDescriptorProto,
+        value_field: &FieldDescriptorProto,
+        entry: &[u8],
+    ) -> Result<(String, Value)> {
+        let mut key = Value::Null;
+        let mut value = Value::Null;
+
+        let mut es = CodedInputStream::from_bytes(entry);
+
+        while !es.eof().map_err(decode_error)? {
+            let (number, wt) = es.read_tag_unpack().map_err(decode_error)?;
+
+            match number {
+                1 => key = self.decode_single(key_field, wt, &mut es)?,
+                2 => value = self.decode_single(value_field, wt, &mut es)?,
+                _ => es.skip_field(wt).map_err(decode_error)?,
+            }
+        }
+
+        let key = match key {
+            Value::String(s) => s,
+            Value::Null => "".to_string(),
+            k => k.to_string(),
+        };
+
+        Ok((key, value))
+    }
+
+    fn decode_single(
+        &self,
+        field: &FieldDescriptorProto,
+        wt: WireType,
+        is: &mut CodedInputStream,
+    ) -> Result<Value> {
+        let t = field.get_field_type();
+
+        if wt != wire_type(t) {
+            return Err(Error::Transport(format!(
+                "failed to decode reply: field {:?} has wire type {:?}, expected {:?}",
+                field.get_name(),
+                wt,
+                wire_type(t)
+            )));
+        }
+
+        match t {
+            Type::TYPE_MESSAGE => {
+                let bytes = is.read_bytes().map_err(decode_error)?;
+
+                self.decode(field.get_type_name(), &bytes)
+            }
+            Type::TYPE_STRING => Ok(Value::String(is.read_string().map_err(decode_error)?)),
+            Type::TYPE_BYTES => Ok(Value::String(base64::encode(
+                is.read_bytes().map_err(decode_error)?,
+            ))),
+            _ => self.decode_scalar(field, is),
+        }
+    }
+
+    // Read a numeric, bool or enum value (without a tag). 64-bit integers
+    // are returned as strings, as the protobuf JSON mapping requires.
+    fn decode_scalar(
+        &self,
+        field: &FieldDescriptorProto,
+        is: &mut CodedInputStream,
+    ) -> Result<Value> {
+        let value = match field.get_field_type() {
+            Type::TYPE_DOUBLE => f64_json(is.read_double().map_err(decode_error)?),
+            Type::TYPE_FLOAT => f64_json(f64::from(is.read_float().map_err(decode_error)?)),
+            Type::TYPE_INT64 => Value::String(is.read_int64().map_err(decode_error)?.to_string()),
+            Type::TYPE_SINT64 => Value::String(is.read_sint64().map_err(decode_error)?.to_string()),
+            Type::TYPE_SFIXED64 => {
+                Value::String(is.read_sfixed64().map_err(decode_error)?.to_string())
+            }
+            Type::TYPE_UINT64 => Value::String(is.read_uint64().map_err(decode_error)?.to_string()),
+            Type::TYPE_FIXED64 => {
+                Value::String(is.read_fixed64().map_err(decode_error)?.to_string())
+            }
+            Type::TYPE_INT32 => Value::from(is.read_int32().map_err(decode_error)?),
+            Type::TYPE_SINT32 => Value::from(is.read_sint32().map_err(decode_error)?),
+            Type::TYPE_SFIXED32 => Value::from(is.read_sfixed32().map_err(decode_error)?),
+            Type::TYPE_UINT32 => Value::from(is.read_uint32().map_err(decode_error)?),
+            Type::TYPE_FIXED32 => Value::from(is.read_fixed32().map_err(decode_error)?),
+            Type::TYPE_BOOL => Value::Bool(is.read_bool().map_err(decode_error)?),
+            Type::TYPE_ENUM => self.enum_json(field, is.read_int32().map_err(decode_error)?),
+            t => {
+                return Err(Error::Transport(format!(
+                    "failed to decode reply: unsupported type {:?} for field {:?}",
+                    t,
+                    field.get_name()
+                )))
+            }
+        };
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use protobuf::descriptor::{
+        EnumValueDescriptorProto, FieldDescriptorProto_Label as Label, MessageOptions,
+    };
+    use protobuf::RepeatedField;
+    use serde_json::json;
+
+    fn field(
+        name: &str,
+        number: i32,
+        t: Type,
+        label: Label,
+        type_name: &str,
+    ) -> FieldDescriptorProto {
+        let mut f = FieldDescriptorProto::new();
+
+        f.set_name(name.to_string());
+        f.set_number(number);
+        f.set_field_type(t);
+        f.set_label(label);
+
+        if !type_name.is_empty() {
+            f.set_type_name(type_name.to_string());
+        }
+
+        f
+    }
+
+    fn optional(name: &str, number: i32, t: Type) -> FieldDescriptorProto {
+        field(name, number, t, Label::LABEL_OPTIONAL, "")
+    }
+
+    fn repeated(name: &str, number: i32, t: Type) -> FieldDescriptorProto {
+        field(name, number, t, Label::LABEL_REPEATED, "")
+    }
+
+    fn message(name: &str, fields: Vec<FieldDescriptorProto>) -> DescriptorProto {
+        let mut m = DescriptorProto::new();
+
+        m.set_name(name.to_string());
+        m.set_field(RepeatedField::from_vec(fields));
+
+        m
+    }
+
+    fn map_entry(name: &str, key: Type, value: FieldDescriptorProto) -> DescriptorProto {
+        let mut value = value;
+
+        value.set_name("value".to_string());
+        value.set_number(2);
+
+        let mut entry = message(name, vec![optional("key", 1, key), value]);
+
+        let mut options = MessageOptions::new();
+        options.set_map_entry(true);
+        entry.set_options(options);
+
+        entry
+    }
+
+    // A pool with the embedded descriptors and ".test.Types", which has
+    // every scalar type (both single and repeated), maps and an enum, none
+    // of which the embedded services use.
+    fn test_pool() -> DescriptorPool {
+        let scalars = [
+            ("double", Type::TYPE_DOUBLE),
+            ("float", Type::TYPE_FLOAT),
+            ("int64", Type::TYPE_INT64),
+            ("uint64", Type::TYPE_UINT64),
+            ("int32", Type::TYPE_INT32),
+            ("fixed64", Type::TYPE_FIXED64),
+            ("fixed32", Type::TYPE_FIXED32),
+            ("bool", Type::TYPE_BOOL),
+            ("string", Type::TYPE_STRING),
+            ("bytes", Type::TYPE_BYTES),
+            ("uint32", Type::TYPE_UINT32),
+            ("sfixed32", Type::TYPE_SFIXED32),
+            ("sfixed64", Type::TYPE_SFIXED64),
+            ("sint32", Type::TYPE_SINT32),
+            ("sint64", Type::TYPE_SINT64),
+        ];
+
+        let mut fields = Vec::new();
+
+        for (i, (name, t)) in scalars.iter().enumerate() {
+            let number = i as i32 + 1;
+
+            fields.push(optional(&format!("{}_value", name), number, *t));
+            fields.push(repeated(&format!("{}_values", name), number + 100, *t));
+        }
+
+        fields.push(field(
+            "colour",
+            200,
+            Type::TYPE_ENUM,
+            Label::LABEL_OPTIONAL,
+            ".test.Colour",
+        ));
+        fields.push(field(
+            "colours",
+            201,
+            Type::TYPE_ENUM,
+            Label::LABEL_REPEATED,
+            ".test.Colour",
+        ));
+        fields.push(field(
+            "child",
+            202,
+            Type::TYPE_MESSAGE,
+            Label::LABEL_OPTIONAL,
+            ".test.Types",
+        ));
+
+        let maps = [
+            ("string_map", "StringMapEntry", Type::TYPE_STRING),
+            ("int32_map", "Int32MapEntry", Type::TYPE_INT32),
+            ("uint64_map", "Uint64MapEntry", Type::TYPE_UINT64),
+            ("bool_map", "BoolMapEntry", Type::TYPE_BOOL),
+        ];
+
+        let mut entries = Vec::new();
+
+        for (i, (name, entry, key)) in maps.iter().enumerate() {
+            let type_name = format!(".test.Types.{}", entry);
+
+            fields.push(field(
+                name,
+                300 + i as i32,
+                Type::TYPE_MESSAGE,
+                Label::LABEL_REPEATED,
+                &type_name,
+            ));
+
+            entries.push(map_entry(entry, *key, optional("", 0, Type::TYPE_STRING)));
+        }
+
+        fields.push(field(
+            "message_map",
+            310,
+            Type::TYPE_MESSAGE,
+            Label::LABEL_REPEATED,
+            ".test.Types.MessageMapEntry",
+        ));
+
+        entries.push(map_entry(
+            "MessageMapEntry",
+            Type::TYPE_STRING,
+            field(
+                "",
+                0,
+                Type::TYPE_MESSAGE,
+                Label::LABEL_OPTIONAL,
+                ".test.Types",
+            ),
+        ));
+
+        let mut types = message("Types", fields);
+        types.set_nested_type(RepeatedField::from_vec(entries));
+
+        let mut colour = EnumDescriptorProto::new();
+        colour.set_name("Colour".to_string());
+
+        for (i, name) in ["RED", "GREEN", "BLUE"].iter().enumerate() {
+            let mut v = EnumValueDescriptorProto::new();
+
+            v.set_name(name.to_string());
+            v.set_number(i as i32);
+
+            colour.mut_value().push(v);
+        }
+
+        let mut file = FileDescriptorProto::new();
+        file.set_name("test.proto".to_string());
+        file.set_package("test".to_string());
+        file.set_syntax("proto3".to_string());
+        file.set_message_type(RepeatedField::from_vec(vec![types]));
+        file.set_enum_type(RepeatedField::from_vec(vec![colour]));
+
+        let mut pool = DescriptorPool::embedded();
+        pool.add_file(file);
+
+        pool
+    }
+
+    fn round_trip(pool: &DescriptorPool, type_name: &str, json: Value) -> Value {
+        let bytes = pool.encode(type_name, &json).unwrap();
+
+        pool.decode(type_name, &bytes).unwrap()
+    }
+
+    // Assert "json" decodes to itself after being encoded as "type_name".
+    fn assert_round_trip(pool: &DescriptorPool, type_name: &str, json: Value) {
+        assert_eq!(round_trip(pool, type_name, json.clone()), json);
+    }
+
+    fn assert_usage_error(pool: &DescriptorPool, json: Value, needle: &str) {
+        match pool.encode(".test.Types", &json) {
+            Err(Error::Usage(msg)) => assert!(
+                msg.contains(needle),
+                "error for {} does not mention {:?}: {}",
+                json,
+                needle,
+                msg
+            ),
+            r => panic!("expected usage error for {}, got {:?}", json, r),
+        }
+    }
+
+    #[test]
+    fn test_embedded_messages() {
+        let pool = DescriptorPool::embedded();
+
+        let tests = vec![
+            (".service.HelloRequest", json!({"name": "world"})),
+            (
+                ".service.ShutdownRequest",
+                json!({"reason": "upgrade", "gracePeriodMs": "1500", "force": true}),
+            ),
+            (
+                ".grpc.health.v1.HealthCheckResponse",
+                json!({"status": "NOT_SERVING"}),
+            ),
+            (
+                ".ttrpc.reflection.v1.ListServicesResponse",
+                json!({"services": [
+                    {"name": "service.MyService", "methods": ["SayHello", "Shutdown"], "file": "service.proto"},
+                    {"name": "grpc.health.v1.Health", "methods": ["Check"]},
+                ]}),
+            ),
+            (
+                ".ttrpc.reflection.v1.FileDescriptorResponse",
+                json!({"fileDescriptorProto": "AAEC/w=="}),
+            ),
+        ];
+
+        for (type_name, json) in tests {
+            assert_round_trip(&pool, type_name, json);
+        }
+    }
+
+    #[test]
+    fn test_embedded_message_matches_generated_code() {
+        let pool = DescriptorPool::embedded();
+
+        let mut req = service::ShutdownRequest::new();
+        req.set_reason("test".to_string());
+        req.set_grace_period_ms(u64::MAX);
+        req.set_force(true);
+
+        let json = json!({"reason": "test", "gracePeriodMs": u64::MAX.to_string(), "force": true});
+
+        let bytes = pool.encode(".service.ShutdownRequest", &json).unwrap();
+
+        assert_eq!(
+            service::ShutdownRequest::parse_from_bytes(&bytes).unwrap(),
+            req
+        );
+
+        let bytes = req.write_to_bytes().unwrap();
+
+        assert_eq!(
+            pool.decode(".service.ShutdownRequest", &bytes).unwrap(),
+            json
+        );
+    }
+
+    #[test]
+    fn test_scalars() {
+        let pool = test_pool();
+
+        let json = json!({
+            "doubleValue": -1.25,
+            "floatValue": 3.5,
+            "int64Value": i64::MIN.to_string(),
+            "uint64Value": u64::MAX.to_string(),
+            "int32Value": i32::MIN,
+            "fixed64Value": u64::MAX.to_string(),
+            "fixed32Value": u32::MAX,
+            "boolValue": true,
+            "stringValue": "héllo",
+            "bytesValue": "AP8Q",
+            "uint32Value": u32::MAX,
+            "sfixed32Value": i32::MIN,
+            "sfixed64Value": i64::MIN.to_string(),
+            "sint32Value": i32::MIN,
+            "sint64Value": i64::MIN.to_string(),
+        });
+
+        assert_round_trip(&pool, ".test.Types", json);
+
+        let json = json!({
+            "doubleValue": "Infinity",
+            "floatValue": "-Infinity",
+            "int32Value": i32::MAX,
+            "sint32Value": -1,
+            "sint64Value": "-1",
+            "boolValue": false,
+        });
+
+        assert_round_trip(&pool, ".test.Types", json);
+
+        let decoded = round_trip(&pool, ".test.Types", json!({"doubleValue": "NaN"}));
+
+        assert_eq!(decoded, json!({"doubleValue": "NaN"}));
+    }
+
+    #[test]
+    fn test_scalar_alternative_forms() {
+        let pool = test_pool();
+
+        // 64-bit integers as numbers, 32-bit as strings, and floats as
+        // strings are accepted but not produced.
+        let tests = vec![
+            (json!({"int64Value": -5}), json!({"int64Value": "-5"})),
+            (json!({"uint64Value": 5}), json!({"uint64Value": "5"})),
+            (json!({"int32Value": "-5"}), json!({"int32Value": -5})),
+            (json!({"uint32Value": "5"}), json!({"uint32Value": 5})),
+            (json!({"doubleValue": "1.5"}), json!({"doubleValue": 1.5})),
+            (json!({"stringValue": null}), json!({})),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(round_trip(&pool, ".test.Types", input), expected);
+        }
+    }
+
+    #[test]
+    fn test_integer_range() {
+        let pool = test_pool();
+
+        let tests = vec![
+            json!({"int32Value": i64::from(i32::MAX) + 1}),
+            json!({"int32Value": i64::from(i32::MIN) - 1}),
+            json!({"sint32Value": "2147483648"}),
+            json!({"sfixed32Value": i64::from(i32::MIN) - 1}),
+            json!({"uint32Value": u64::from(u32::MAX) + 1}),
+            json!({"fixed32Value": "4294967296"}),
+            json!({"int32Values": [1, 2147483648_i64]}),
+            json!({"floatValue": 1e300}),
+        ];
+
+        for json in tests {
+            let name = json.as_object().unwrap().keys().next().unwrap().clone();
+
+            assert_usage_error(&pool, json, &name.replace("Value", "_value"));
+        }
+
+        let tests = vec![
+            json!({"uint32Value": -1}),
+            json!({"uint64Value": -1}),
+            json!({"int64Value": "9223372036854775808"}),
+            json!({"colour": 2147483648_i64}),
+        ];
+
+        for json in tests {
+            assert_usage_error(&pool, json, "invalid value");
+        }
+    }
+
+    #[test]
+    fn test_repeated() {
+        let pool = test_pool();
+
+        let json = json!({
+            "doubleValues": [1.5, -2.0],
+            "floatValues": [0.5],
+            "int64Values": ["1", "-1"],
+            "uint64Values": ["0", u64::MAX.to_string()],
+            "int32Values": [1, -1, i32::MAX],
+            "fixed64Values": ["7"],
+            "fixed32Values": [7, 8],
+            "boolValues": [true, false, true],
+            "stringValues": ["a", "", "b"],
+            "bytesValues": ["AQ==", "AQI=", "AQID"],
+            "uint32Values": [0, u32::MAX],
+            "sfixed32Values": [-7],
+            "sfixed64Values": ["-7"],
+            "sint32Values": [-1, 1],
+            "sint64Values": ["-1", "1"],
+            "colours": ["BLUE", "RED"],
+        });
+
+        assert_round_trip(&pool, ".test.Types", json);
+    }
+
+    #[test]
+    fn test_packed_and_unpacked() {
+        let pool = test_pool();
+
+        let json = json!({"int32Values": [1, 2, 300]});
+
+        let bytes = pool.encode(".test.Types", &json).unwrap();
+
+        // Encoded packed: one length-delimited field (105).
+        assert_eq!(bytes, vec![0xca, 0x06, 4, 1, 2, 0xac, 0x02]);
+
+        // Decoding accepts unpacked values too, and both forms together.
+        let unpacked = vec![0xc8, 0x06, 1, 0xc8, 0x06, 2, 0xca, 0x06, 2, 0xac, 0x02];
+
+        assert_eq!(pool.decode(".test.Types", &unpacked).unwrap(), json);
+    }
+
+    #[test]
+    fn test_maps() {
+        let pool = test_pool();
+
+        let json = json!({
+            "stringMap": {"a": "1", "": "empty key"},
+            "int32Map": {"-1": "minus one", "2147483647": "max"},
+            "uint64Map": {"18446744073709551615": "max"},
+            "boolMap": {"true": "yes", "false": "no"},
+            "messageMap": {"child": {"stringValue": "nested", "int32Values": [1]}},
+        });
+
+        assert_round_trip(&pool, ".test.Types", json);
+    }
+
+    #[test]
+    fn test_map_keys() {
+        let pool = test_pool();
+
+        assert_usage_error(&pool, json!({"boolMap": {"yes": "x"}}), "bool_map");
+        assert_usage_error(&pool, json!({"boolMap": {"True": "x"}}), "bool_map");
+        assert_usage_error(&pool, json!({"boolMap": {"1": "x"}}), "bool_map");
+        assert_usage_error(&pool, json!({"int32Map": {"2147483648": "x"}}), "key");
+        assert_usage_error(&pool, json!({"int32Map": {"one": "x"}}), "key");
+        assert_usage_error(&pool, json!({"stringMap": ["a"]}), "string_map");
+    }
+
+    #[test]
+    fn test_enums() {
+        let pool = test_pool();
+
+        assert_round_trip(&pool, ".test.Types", json!({"colour": "GREEN"}));
+
+        // By number, decoding to the name.
+        assert_eq!(
+            round_trip(
+                &pool,
+                ".test.Types",
+                json!({"colour": 2, "colours": [0, "GREEN"]})
+            ),
+            json!({"colour": "BLUE", "colours": ["RED", "GREEN"]})
+        );
+
+        // Unknown numbers are kept.
+        assert_round_trip(&pool, ".test.Types", json!({"colour": 7}));
+
+        assert_usage_error(&pool, json!({"colour": "PURPLE"}), "colour");
+
+        let pool = DescriptorPool::embedded();
+
+        assert_eq!(
+            round_trip(
+                &pool,
+                ".grpc.health.v1.HealthCheckResponse",
+                json!({"status": 1})
+            ),
+            json!({"status": "SERVING"})
+        );
+    }
+
+    #[test]
+    fn test_nested_messages() {
+        let pool = test_pool();
+
+        let json = json!({
+            "stringValue": "parent",
+            "child": {
+                "int32Value": 1,
+                "child": {"colours": ["BLUE"], "child": {}},
+            },
+        });
+
+        assert_round_trip(&pool, ".test.Types", json);
+
+        assert_usage_error(&pool, json!({"child": {"noSuchField": 1}}), "noSuchField");
+        assert_usage_error(&pool, json!({"child": "x"}), "expected a JSON object");
+    }
+
+    #[test]
+    fn test_field_names() {
+        let pool = test_pool();
+
+        // Both the proto and JSON names are accepted.
+        assert_eq!(
+            round_trip(
+                &pool,
+                ".test.Types",
+                json!({"string_value": "a", "int32Value": 1})
+            ),
+            json!({"stringValue": "a", "int32Value": 1})
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        let pool = test_pool();
+
+        let tests = vec![
+            ("", ""),
+            ("AA==", "AA=="),
+            ("AA", "AA=="),
+            ("+/+/", "+/+/"),
+            ("-_-_", "+/+/"),
+            ("aGVsbG8", "aGVsbG8="),
+            ("aGVsbG8=", "aGVsbG8="),
+        ];
+
+        for (input, expected) in tests {
+            assert_eq!(
+                round_trip(&pool, ".test.Types", json!({ "bytesValue": input })),
+                json!({ "bytesValue": expected }),
+                "decoding {:?}",
+                input
+            );
+        }
+
+        for invalid in &["A", "AA=A", "a*b=", "+/-_"] {
+            assert_usage_error(&pool, json!({ "bytesValue": invalid }), "bytes_value");
+        }
+
+        let all: Vec<u8> = (0..=255).collect();
+
+        assert_eq!(
+            pool.decode(
+                ".test.Types",
+                &pool
+                    .encode(".test.Types", &json!({"bytesValue": base64::encode(&all)}))
+                    .unwrap()
+            )
+            .unwrap(),
+            json!({"bytesValue": base64::encode(&all)})
+        );
+    }
+}
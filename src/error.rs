@@ -0,0 +1,134 @@
+// Description: Error type shared by the client and server
+
+use std::fmt;
+use std::time::Duration;
+use ttrpc::ttrpc::Code;
+
+/// Process exit codes, one per [`Error`] category.
+///
+/// | Code  | Meaning                                              |
+/// |-------|------------------------------------------------------|
+/// | 0     | Success (a server was stopped by a Shutdown request) |
+/// | 2     | Invalid arguments or client command                  |
+/// | 3     | Malformed server URI                                 |
+/// | 4     | Failed to create or bind a socket                    |
+/// | 5     | Failed to connect to the server                      |
+/// | 6     | VSOCK error                                          |
+/// | 7     | ttRPC transport error                                |
+/// | 8     | Server returned a non-OK RPC status                  |
+/// | 9     | RPC deadline exceeded                                |
+/// | 10    | Failed to read or write a file                       |
+/// | 11    | Health check reported service not serving            |
+/// | 12    | Server shut down by the program running it           |
+/// | 128+N | Server shut down by signal N                         |
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+    pub const USAGE: i32 = 2;
+    pub const URI: i32 = 3;
+    pub const SOCKET: i32 = 4;
+    pub const CONNECT: i32 = 5;
+    pub const VSOCK: i32 = 6;
+    pub const TRANSPORT: i32 = 7;
+    pub const RPC_STATUS: i32 = 8;
+    pub const DEADLINE: i32 = 9;
+    pub const IO: i32 = 10;
+    pub const UNHEALTHY: i32 = 11;
+
+    /// A server stopped other than by a `Shutdown` request or a signal.
+    pub const LOCAL_SHUTDOWN: i32 = 12;
+
+    /// Added to the signal number when a signal stops the server.
+    pub const SIGNAL_BASE: i32 = 128;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// Invalid arguments or client command.
+    Usage(String),
+
+    /// The server URI could not be parsed.
+    Uri(String),
+
+    /// A Unix socket could not be created or bound.
+    Socket(String),
+
+    /// The client could not connect to the server.
+    Connect(String),
+
+    /// A VSOCK socket could not be created or connected.
+    Vsock(String),
+
+    /// The ttRPC layer failed to send or receive a message.
+    Transport(String),
+
+    /// The server handled the request but returned a non-OK status.
+    RpcStatus { code: Code, message: String },
+
+    /// The server did not reply to `method` before the deadline.
+    Deadline { method: String, elapsed: Duration },
+
+    /// A file could not be read or written.
+    Io(String),
+
+    /// A health check found `service` was not serving.
+    Unhealthy { service: String, status: String },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// The process exit code for this category of error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Usage(_) => exit_code::USAGE,
+            Error::Uri(_) => exit_code::URI,
+            Error::Socket(_) => exit_code::SOCKET,
+            Error::Connect(_) => exit_code::CONNECT,
+            Error::Vsock(_) => exit_code::VSOCK,
+            Error::Transport(_) => exit_code::TRANSPORT,
+            Error::RpcStatus { .. } => exit_code::RPC_STATUS,
+            Error::Deadline { .. } => exit_code::DEADLINE,
+            Error::Io(_) => exit_code::IO,
+            Error::Unhealthy { .. } => exit_code::UNHEALTHY,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage(s) => write!(f, "{}", s),
+            Error::Uri(s) => write!(f, "invalid URI: {}", s),
+            Error::Socket(s) => write!(f, "socket error: {}", s),
+            Error::Connect(s) => write!(f, "connect error: {}", s),
+            Error::Vsock(s) => write!(f, "VSOCK error: {}", s),
+            Error::Transport(s) => write!(f, "ttRPC transport error: {}", s),
+            Error::RpcStatus { code, message } => {
+                write!(f, "RPC failed with status {:?}: {}", code, message)
+            }
+            Error::Deadline { method, elapsed } => write!(
+                f,
+                "deadline exceeded calling {} after {:?}",
+                method, elapsed
+            ),
+            Error::Io(s) => write!(f, "I/O error: {}", s),
+            Error::Unhealthy { service, status } => {
+                write!(f, "service {:?} is not serving: {}", service, status)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl From<ttrpc::error::Error> for Error {
+    fn from(e: ttrpc::error::Error) -> Self {
+        match e {
+            ttrpc::error::Error::RpcStatus(s) => Error::RpcStatus {
+                code: s.get_code(),
+                message: s.get_message().to_string(),
+            },
+            e => Error::Transport(format!("{:?}", e)),
+        }
+    }
+}
@@ -0,0 +1,711 @@
+// This file is generated by rust-protobuf 2.14.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `health.proto`
+
+use protobuf::Message as Message_imported_for_functions;
+use protobuf::ProtobufEnum as ProtobufEnum_imported_for_functions;
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_14_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct HealthCheckRequest {
+    // message fields
+    pub service: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a HealthCheckRequest {
+    fn default() -> &'a HealthCheckRequest {
+        <HealthCheckRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl HealthCheckRequest {
+    pub fn new() -> HealthCheckRequest {
+        ::std::default::Default::default()
+    }
+
+    // string service = 1;
+
+
+    pub fn get_service(&self) -> &str {
+        &self.service
+    }
+    pub fn clear_service(&mut self) {
+        self.service.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_service(&mut self, v: ::std::string::String) {
+        self.service = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_service(&mut self) -> &mut ::std::string::String {
+        &mut self.service
+    }
+
+    // Take field
+    pub fn take_service(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.service, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for HealthCheckRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.service)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.service.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.service);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.service.is_empty() {
+            os.write_string(1, &self.service)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> HealthCheckRequest {
+        HealthCheckRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "service",
+                    |m: &HealthCheckRequest| { &m.service },
+                    |m: &mut HealthCheckRequest| { &mut m.service },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<HealthCheckRequest>(
+                    "HealthCheckRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static HealthCheckRequest {
+        static mut instance: ::protobuf::lazy::Lazy<HealthCheckRequest> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(HealthCheckRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for HealthCheckRequest {
+    fn clear(&mut self) {
+        self.service.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for HealthCheckRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HealthCheckRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct HealthCheckResponse {
+    // message fields
+    pub status: HealthCheckResponse_ServingStatus,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a HealthCheckResponse {
+    fn default() -> &'a HealthCheckResponse {
+        <HealthCheckResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl HealthCheckResponse {
+    pub fn new() -> HealthCheckResponse {
+        ::std::default::Default::default()
+    }
+
+    // .grpc.health.v1.HealthCheckResponse.ServingStatus status = 1;
+
+
+    pub fn get_status(&self) -> HealthCheckResponse_ServingStatus {
+        self.status
+    }
+    pub fn clear_status(&mut self) {
+        self.status = HealthCheckResponse_ServingStatus::UNKNOWN;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_status(&mut self, v: HealthCheckResponse_ServingStatus) {
+        self.status = v;
+    }
+}
+
+impl ::protobuf::Message for HealthCheckResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.status, 1, &mut self.unknown_fields)?
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if self.status != HealthCheckResponse_ServingStatus::UNKNOWN {
+            my_size += ::protobuf::rt::enum_size(1, self.status);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if self.status != HealthCheckResponse_ServingStatus::UNKNOWN {
+            os.write_enum(1, ::protobuf::ProtobufEnum::value(&self.status))?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> HealthCheckResponse {
+        HealthCheckResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<HealthCheckResponse_ServingStatus>>(
+                    "status",
+                    |m: &HealthCheckResponse| { &m.status },
+                    |m: &mut HealthCheckResponse| { &mut m.status },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<HealthCheckResponse>(
+                    "HealthCheckResponse",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static HealthCheckResponse {
+        static mut instance: ::protobuf::lazy::Lazy<HealthCheckResponse> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(HealthCheckResponse::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for HealthCheckResponse {
+    fn clear(&mut self) {
+        self.status = HealthCheckResponse_ServingStatus::UNKNOWN;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for HealthCheckResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HealthCheckResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(Clone,PartialEq,Eq,Debug,Hash)]
+pub enum HealthCheckResponse_ServingStatus {
+    UNKNOWN = 0,
+    SERVING = 1,
+    NOT_SERVING = 2,
+    SERVICE_UNKNOWN = 3,
+}
+
+impl ::protobuf::ProtobufEnum for HealthCheckResponse_ServingStatus {
+    fn value(&self) -> i32 {
+        *self as i32
+    }
+
+    fn from_i32(value: i32) -> ::std::option::Option<HealthCheckResponse_ServingStatus> {
+        match value {
+            0 => ::std::option::Option::Some(HealthCheckResponse_ServingStatus::UNKNOWN),
+            1 => ::std::option::Option::Some(HealthCheckResponse_ServingStatus::SERVING),
+            2 => ::std::option::Option::Some(HealthCheckResponse_ServingStatus::NOT_SERVING),
+            3 => ::std::option::Option::Some(HealthCheckResponse_ServingStatus::SERVICE_UNKNOWN),
+            _ => ::std::option::Option::None
+        }
+    }
+
+    fn values() -> &'static [Self] {
+        static values: &'static [HealthCheckResponse_ServingStatus] = &[
+            HealthCheckResponse_ServingStatus::UNKNOWN,
+            HealthCheckResponse_ServingStatus::SERVING,
+            HealthCheckResponse_ServingStatus::NOT_SERVING,
+            HealthCheckResponse_ServingStatus::SERVICE_UNKNOWN,
+        ];
+        values
+    }
+
+    fn enum_descriptor_static() -> &'static ::protobuf::reflect::EnumDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::EnumDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                ::protobuf::reflect::EnumDescriptor::new_pb_name::<HealthCheckResponse_ServingStatus>("HealthCheckResponse.ServingStatus", file_descriptor_proto())
+            })
+        }
+    }
+}
+
+impl ::std::marker::Copy for HealthCheckResponse_ServingStatus {
+}
+
+impl ::std::default::Default for HealthCheckResponse_ServingStatus {
+    fn default() -> Self {
+        HealthCheckResponse_ServingStatus::UNKNOWN
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HealthCheckResponse_ServingStatus {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Enum(::protobuf::ProtobufEnum::descriptor(self))
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct HealthWatchRequest {
+    // message fields
+    pub service: ::std::string::String,
+    pub last_status: HealthCheckResponse_ServingStatus,
+    pub wait_ms: u64,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a HealthWatchRequest {
+    fn default() -> &'a HealthWatchRequest {
+        <HealthWatchRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl HealthWatchRequest {
+    pub fn new() -> HealthWatchRequest {
+        ::std::default::Default::default()
+    }
+
+    // string service = 1;
+
+
+    pub fn get_service(&self) -> &str {
+        &self.service
+    }
+    pub fn clear_service(&mut self) {
+        self.service.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_service(&mut self, v: ::std::string::String) {
+        self.service = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_service(&mut self) -> &mut ::std::string::String {
+        &mut self.service
+    }
+
+    // Take field
+    pub fn take_service(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.service, ::std::string::String::new())
+    }
+
+    // .grpc.health.v1.HealthCheckResponse.ServingStatus last_status = 2;
+
+
+    pub fn get_last_status(&self) -> HealthCheckResponse_ServingStatus {
+        self.last_status
+    }
+    pub fn clear_last_status(&mut self) {
+        self.last_status = HealthCheckResponse_ServingStatus::UNKNOWN;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_last_status(&mut self, v: HealthCheckResponse_ServingStatus) {
+        self.last_status = v;
+    }
+
+    // uint64 wait_ms = 3;
+
+
+    pub fn get_wait_ms(&self) -> u64 {
+        self.wait_ms
+    }
+    pub fn clear_wait_ms(&mut self) {
+        self.wait_ms = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_wait_ms(&mut self, v: u64) {
+        self.wait_ms = v;
+    }
+}
+
+impl ::protobuf::Message for HealthWatchRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.service)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_proto3_enum_with_unknown_fields_into(wire_type, is, &mut self.last_status, 2, &mut self.unknown_fields)?
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.wait_ms = tmp;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.service.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.service);
+        }
+        if self.last_status != HealthCheckResponse_ServingStatus::UNKNOWN {
+            my_size += ::protobuf::rt::enum_size(2, self.last_status);
+        }
+        if self.wait_ms != 0 {
+            my_size += ::protobuf::rt::value_size(3, self.wait_ms, ::protobuf::wire_format::WireTypeVarint);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.service.is_empty() {
+            os.write_string(1, &self.service)?;
+        }
+        if self.last_status != HealthCheckResponse_ServingStatus::UNKNOWN {
+            os.write_enum(2, ::protobuf::ProtobufEnum::value(&self.last_status))?;
+        }
+        if self.wait_ms != 0 {
+            os.write_uint64(3, self.wait_ms)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> HealthWatchRequest {
+        HealthWatchRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "service",
+                    |m: &HealthWatchRequest| { &m.service },
+                    |m: &mut HealthWatchRequest| { &mut m.service },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeEnum<HealthCheckResponse_ServingStatus>>(
+                    "last_status",
+                    |m: &HealthWatchRequest| { &m.last_status },
+                    |m: &mut HealthWatchRequest| { &mut m.last_status },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "wait_ms",
+                    |m: &HealthWatchRequest| { &m.wait_ms },
+                    |m: &mut HealthWatchRequest| { &mut m.wait_ms },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<HealthWatchRequest>(
+                    "HealthWatchRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static HealthWatchRequest {
+        static mut instance: ::protobuf::lazy::Lazy<HealthWatchRequest> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(HealthWatchRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for HealthWatchRequest {
+    fn clear(&mut self) {
+        self.service.clear();
+        self.last_status = HealthCheckResponse_ServingStatus::UNKNOWN;
+        self.wait_ms = 0;
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for HealthWatchRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for HealthWatchRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x0chealth.proto\x12\x0egrpc.health.v1\".\n\x12HealthCheckRequest\x12\
+    \x18\n\x07service\x18\x01\x20\x01(\tR\x07service\"\xb1\x01\n\x13HealthCh\
+    eckResponse\x12I\n\x06status\x18\x01\x20\x01(\x0e21.grpc.health.v1.Healt\
+    hCheckResponse.ServingStatusR\x06status\"O\n\rServingStatus\x12\x0b\n\
+    \x07UNKNOWN\x10\0\x12\x0b\n\x07SERVING\x10\x01\x12\x0f\n\x0bNOT_SERVING\
+    \x10\x02\x12\x13\n\x0fSERVICE_UNKNOWN\x10\x03\"\x9b\x01\n\x12HealthWatch\
+    Request\x12\x18\n\x07service\x18\x01\x20\x01(\tR\x07service\x12R\n\x0bla\
+    st_status\x18\x02\x20\x01(\x0e21.grpc.health.v1.HealthCheckResponse.Serv\
+    ingStatusR\nlastStatus\x12\x17\n\x07wait_ms\x18\x03\x20\x01(\x04R\x06wai\
+    tMs2\xb0\x01\n\x06Health\x12R\n\x05Check\x12\".grpc.health.v1.HealthChec\
+    kRequest\x1a#.grpc.health.v1.HealthCheckResponse\"\0\x12R\n\x05Watch\x12\
+    \".grpc.health.v1.HealthWatchRequest\x1a#.grpc.health.v1.HealthCheckResp\
+    onse\"\0J\xf4\x0b\n\x06\x12\x04\0\0+\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\
+    \n\xb1\x01\n\x01\x02\x12\x03\x04\0\x17\x1a\xa6\x01\x20Modelled\x20on\x20\
+    grpc.health.v1\x20(https://github.com/grpc/grpc/blob/master/doc/health-c\
+    hecking.md).\n\x20ttRPC\x20has\x20no\x20streaming,\x20so\x20Watch\x20is\
+    \x20a\x20long\x20poll\x20rather\x20than\x20a\x20stream.\n\n\n\n\x02\x04\
+    \0\x12\x04\x06\0\t\x01\n\n\n\x03\x04\0\x01\x12\x03\x06\x08\x1a\nE\n\x04\
+    \x04\0\x02\0\x12\x03\x08\x02\x15\x1a8\x20The\x20service\x20to\x20check\
+    \x20(\"\"\x20means\x20the\x20server\x20as\x20a\x20whole).\n\n\x0c\n\x05\
+    \x04\0\x02\0\x05\x12\x03\x08\x02\x08\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\
+    \x08\t\x10\n\x0c\n\x05\x04\0\x02\0\x03\x12\x03\x08\x13\x14\n\n\n\x02\x04\
+    \x01\x12\x04\x0b\0\x13\x01\n\n\n\x03\x04\x01\x01\x12\x03\x0b\x08\x1b\n\
+    \x0c\n\x04\x04\x01\x04\0\x12\x04\x0c\x02\x11\x03\n\x0c\n\x05\x04\x01\x04\
+    \0\x01\x12\x03\x0c\x07\x14\n\r\n\x06\x04\x01\x04\0\x02\0\x12\x03\r\x04\
+    \x10\n\x0e\n\x07\x04\x01\x04\0\x02\0\x01\x12\x03\r\x04\x0b\n\x0e\n\x07\
+    \x04\x01\x04\0\x02\0\x02\x12\x03\r\x0e\x0f\n\r\n\x06\x04\x01\x04\0\x02\
+    \x01\x12\x03\x0e\x04\x10\n\x0e\n\x07\x04\x01\x04\0\x02\x01\x01\x12\x03\
+    \x0e\x04\x0b\n\x0e\n\x07\x04\x01\x04\0\x02\x01\x02\x12\x03\x0e\x0e\x0f\n\
+    \r\n\x06\x04\x01\x04\0\x02\x02\x12\x03\x0f\x04\x14\n\x0e\n\x07\x04\x01\
+    \x04\0\x02\x02\x01\x12\x03\x0f\x04\x0f\n\x0e\n\x07\x04\x01\x04\0\x02\x02\
+    \x02\x12\x03\x0f\x12\x13\n/\n\x06\x04\x01\x04\0\x02\x03\x12\x03\x10\x04\
+    \x18\"\x20\x20Used\x20only\x20by\x20the\x20Watch\x20method.\n\n\x0e\n\
+    \x07\x04\x01\x04\0\x02\x03\x01\x12\x03\x10\x04\x13\n\x0e\n\x07\x04\x01\
+    \x04\0\x02\x03\x02\x12\x03\x10\x16\x17\n\x0b\n\x04\x04\x01\x02\0\x12\x03\
+    \x12\x02\x1b\n\x0c\n\x05\x04\x01\x02\0\x06\x12\x03\x12\x02\x0f\n\x0c\n\
+    \x05\x04\x01\x02\0\x01\x12\x03\x12\x10\x16\n\x0c\n\x05\x04\x01\x02\0\x03\
+    \x12\x03\x12\x19\x1a\n\n\n\x02\x04\x02\x12\x04\x15\0\x20\x01\n\n\n\x03\
+    \x04\x02\x01\x12\x03\x15\x08\x1a\nE\n\x04\x04\x02\x02\0\x12\x03\x17\x02\
+    \x15\x1a8\x20The\x20service\x20to\x20watch\x20(\"\"\x20means\x20the\x20s\
+    erver\x20as\x20a\x20whole).\n\n\x0c\n\x05\x04\x02\x02\0\x05\x12\x03\x17\
+    \x02\x08\n\x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x17\t\x10\n\x0c\n\x05\x04\
+    \x02\x02\0\x03\x12\x03\x17\x13\x14\nf\n\x04\x04\x02\x02\x01\x12\x03\x1b\
+    \x024\x1aY\x20The\x20status\x20the\x20caller\x20last\x20saw.\x20Watch\
+    \x20replies\x20as\x20soon\x20as\x20the\x20status\n\x20differs\x20from\
+    \x20this.\n\n\x0c\n\x05\x04\x02\x02\x01\x06\x12\x03\x1b\x02#\n\x0c\n\x05\
+    \x04\x02\x02\x01\x01\x12\x03\x1b$/\n\x0c\n\x05\x04\x02\x02\x01\x03\x12\
+    \x03\x1b23\n\x9a\x01\n\x04\x04\x02\x02\x02\x12\x03\x1f\x02\x15\x1a\x8c\
+    \x01\x20How\x20long\x20to\x20wait\x20for\x20the\x20status\x20to\x20chang\
+    e\x20before\x20replying\x20with\x20the\n\x20current\x20status\x20anyway,\
+    \x20in\x20milliseconds\x20(0\x20means\x20the\x20server's\x20default).\n\
+    \n\x0c\n\x05\x04\x02\x02\x02\x05\x12\x03\x1f\x02\x08\n\x0c\n\x05\x04\x02\
+    \x02\x02\x01\x12\x03\x1f\t\x10\n\x0c\n\x05\x04\x02\x02\x02\x03\x12\x03\
+    \x1f\x13\x14\n\n\n\x02\x06\0\x12\x04\"\0+\x01\n\n\n\x03\x06\0\x01\x12\
+    \x03\"\x08\x0e\n`\n\x04\x06\0\x02\0\x12\x03%\x02A\x1aS\x20Returns\x20the\
+    \x20status\x20of\x20a\x20service,\x20or\x20a\x20NOT_FOUND\x20error\x20if\
+    \x20the\x20service\n\x20is\x20unknown.\n\n\x0c\n\x05\x06\0\x02\0\x01\x12\
+    \x03%\x06\x0b\n\x0c\n\x05\x06\0\x02\0\x02\x12\x03%\r\x1f\n\x0c\n\x05\x06\
+    \0\x02\0\x03\x12\x03%*=\n\xa9\x01\n\x04\x06\0\x02\x01\x12\x03*\x02A\x1a\
+    \x9b\x01\x20Waits\x20for\x20the\x20status\x20of\x20a\x20service\x20to\
+    \x20change.\x20Unknown\x20services\x20are\n\x20reported\x20as\x20SERVICE\
+    _UNKNOWN\x20rather\x20than\x20an\x20error,\x20since\x20they\x20may\x20be\
+    \n\x20registered\x20later.\n\n\x0c\n\x05\x06\0\x02\x01\x01\x12\x03*\x06\
+    \x0b\n\x0c\n\x05\x06\0\x02\x01\x02\x12\x03*\r\x1f\n\x0c\n\x05\x06\0\x02\
+    \x01\x03\x12\x03**=b\x06proto3\
+";
+
+static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    unsafe {
+        file_descriptor_proto_lazy.get(|| {
+            parse_descriptor_proto()
+        })
+    }
+}
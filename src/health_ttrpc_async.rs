@@ -0,0 +1,90 @@
+// This file is generated by ttrpc-compiler 0.4.0. Do not edit
+// @generated
+
+// https://github.com/Manishearth/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clipto_camel_casepy)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+use protobuf::{CodedInputStream, CodedOutputStream, Message};
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+
+#[derive(Clone)]
+pub struct HealthClient {
+    client: ::ttrpc::r#async::Client,
+}
+
+impl HealthClient {
+    pub fn new(client: ::ttrpc::r#async::Client) -> Self {
+        HealthClient {
+            client: client,
+        }
+    }
+
+    pub async fn check(&self, req: &super::health::HealthCheckRequest, timeout_nano: i64) -> ::ttrpc::Result<super::health::HealthCheckResponse> {
+        let mut cres = super::health::HealthCheckResponse::new();
+        ::ttrpc::async_client_request!(self, req, timeout_nano, "grpc.health.v1.Health", "Check", cres);
+    }
+
+    pub async fn watch(&self, req: &super::health::HealthWatchRequest, timeout_nano: i64) -> ::ttrpc::Result<super::health::HealthCheckResponse> {
+        let mut cres = super::health::HealthCheckResponse::new();
+        ::ttrpc::async_client_request!(self, req, timeout_nano, "grpc.health.v1.Health", "Watch", cres);
+    }
+}
+
+struct CheckMethod {
+    service: Arc<std::boxed::Box<dyn Health + Send + Sync>>,
+}
+
+#[async_trait]
+impl ::ttrpc::r#async::MethodHandler for CheckMethod {
+    async fn handler(&self, ctx: ::ttrpc::r#async::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<(u32, Vec<u8>)> {
+        ::ttrpc::async_request_handler!(self, ctx, req, health, HealthCheckRequest, check);
+    }
+}
+
+struct WatchMethod {
+    service: Arc<std::boxed::Box<dyn Health + Send + Sync>>,
+}
+
+#[async_trait]
+impl ::ttrpc::r#async::MethodHandler for WatchMethod {
+    async fn handler(&self, ctx: ::ttrpc::r#async::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<(u32, Vec<u8>)> {
+        ::ttrpc::async_request_handler!(self, ctx, req, health, HealthWatchRequest, watch);
+    }
+}
+
+#[async_trait]
+pub trait Health: Sync {
+    async fn check(&self, _ctx: &::ttrpc::r#async::TtrpcContext, _req: super::health::HealthCheckRequest) -> ::ttrpc::Result<super::health::HealthCheckResponse> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/grpc.health.v1.Health/Check is not supported".to_string())))
+    }
+    async fn watch(&self, _ctx: &::ttrpc::r#async::TtrpcContext, _req: super::health::HealthWatchRequest) -> ::ttrpc::Result<super::health::HealthCheckResponse> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/grpc.health.v1.Health/Watch is not supported".to_string())))
+    }
+}
+
+pub fn create_health(service: Arc<std::boxed::Box<dyn Health + Send + Sync>>) -> HashMap <String, Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>> {
+    let mut methods = HashMap::new();
+
+    methods.insert("/grpc.health.v1.Health/Check".to_string(),
+                    std::boxed::Box::new(CheckMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>);
+
+    methods.insert("/grpc.health.v1.Health/Watch".to_string(),
+                    std::boxed::Box::new(WatchMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>);
+
+    methods
+}
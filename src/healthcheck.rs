@@ -0,0 +1,162 @@
+// Description: Health checking modelled on grpc.health.v1
+
+use crate::health::{
+    HealthCheckRequest, HealthCheckResponse, HealthCheckResponse_ServingStatus as ServingStatus,
+    HealthWatchRequest,
+};
+use crate::health_ttrpc::Health;
+use crate::server::{request_context, rpc_error};
+use crate::types::RequestContext;
+use ttrpc::error::Result as TResult;
+use ttrpc::ttrpc::Code;
+use ttrpc::TtrpcContext;
+
+use slog::info;
+use std::collections::HashMap;
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
+
+/// Name under which the health service reports its own status.
+pub const HEALTH_SERVICE_NAME: &str = "grpc.health.v1.Health";
+
+/// Name under which the health service reports the status of `MyService`.
+pub const MY_SERVICE_NAME: &str = "service.MyService";
+
+const CHECK_METHOD: &str = "Check";
+const WATCH_METHOD: &str = "Watch";
+
+// How long Watch waits for a change if the request does not say.
+const DEFAULT_WATCH_WAIT: Duration = Duration::from_secs(30);
+
+// The longest Watch will wait for a change, so a handler thread is never
+// tied up indefinitely.
+const MAX_WATCH_WAIT: Duration = Duration::from_secs(5 * 60);
+
+/// The serving status of each service, as reported by the `Health` RPCs.
+/// The empty service name denotes the server as a whole.
+#[derive(Debug, Clone, Default)]
+pub struct HealthService {
+    state: Arc<(Mutex<HashMap<String, ServingStatus>>, Condvar)>,
+}
+
+impl HealthService {
+    pub fn new() -> Self {
+        HealthService::default()
+    }
+
+    /// Set the status of `service`, waking any callers watching it.
+    pub fn set_status(&self, service: &str, status: ServingStatus) {
+        let (statuses, changed) = &*self.state;
+
+        let old = statuses.lock().unwrap().insert(service.to_string(), status);
+
+        if old != Some(status) {
+            info!(sl!(), "health status changed";
+                "service" => service,
+                "status" => format!("{:?}", status));
+
+            changed.notify_all();
+        }
+    }
+
+    /// Mark every service as not serving, as happens when the server
+    /// starts shutting down.
+    pub fn set_all_not_serving(&self) {
+        let services: Vec<String> = self.state.0.lock().unwrap().keys().cloned().collect();
+
+        for service in services {
+            self.set_status(&service, ServingStatus::NOT_SERVING);
+        }
+    }
+
+    /// The status of `service`, or `None` if it has never been set.
+    pub fn status(&self, service: &str) -> Option<ServingStatus> {
+        self.state.0.lock().unwrap().get(service).copied()
+    }
+
+    // Wait up to "wait" for the status of "service" to differ from "last",
+    // returning the status at that point. Unknown services are reported as
+    // SERVICE_UNKNOWN.
+    fn wait_for_change(&self, service: &str, last: ServingStatus, wait: Duration) -> ServingStatus {
+        let (statuses, changed) = &*self.state;
+
+        let deadline = Instant::now() + wait;
+
+        let mut statuses = statuses.lock().unwrap();
+
+        loop {
+            let status = statuses
+                .get(service)
+                .copied()
+                .unwrap_or(ServingStatus::SERVICE_UNKNOWN);
+
+            let now = Instant::now();
+
+            if status != last || now >= deadline {
+                return status;
+            }
+
+            statuses = changed.wait_timeout(statuses, deadline - now).unwrap().0;
+        }
+    }
+}
+
+fn health_response(status: ServingStatus) -> HealthCheckResponse {
+    let mut resp = HealthCheckResponse::new();
+
+    resp.set_status(status);
+
+    resp
+}
+
+// The Check and Watch handlers, shared by the sync and async services,
+// which call them within the request's logging scope (see
+// RequestContext::scope).
+impl HealthService {
+    pub(crate) fn handle_check(
+        &self,
+        ctx: &RequestContext,
+        req: HealthCheckRequest,
+    ) -> TResult<HealthCheckResponse> {
+        ctx.log_request(HEALTH_SERVICE_NAME, CHECK_METHOD);
+
+        match self.status(req.get_service()) {
+            Some(status) => Ok(health_response(status)),
+            None => Err(rpc_error(
+                Code::NOT_FOUND,
+                format!("unknown service {:?}", req.get_service()),
+            )),
+        }
+    }
+
+    pub(crate) fn handle_watch(
+        &self,
+        ctx: &RequestContext,
+        req: HealthWatchRequest,
+    ) -> TResult<HealthCheckResponse> {
+        ctx.log_request(HEALTH_SERVICE_NAME, WATCH_METHOD);
+
+        let wait = match req.get_wait_ms() {
+            0 => DEFAULT_WATCH_WAIT,
+            ms => Duration::from_millis(ms).min(MAX_WATCH_WAIT),
+        };
+
+        let status = self.wait_for_change(req.get_service(), req.get_last_status(), wait);
+
+        Ok(health_response(status))
+    }
+}
+
+impl Health for HealthService {
+    fn check(&self, ctx: &TtrpcContext, req: HealthCheckRequest) -> TResult<HealthCheckResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_check(&ctx, req))
+    }
+
+    fn watch(&self, ctx: &TtrpcContext, req: HealthWatchRequest) -> TResult<HealthCheckResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_watch(&ctx, req))
+    }
+}
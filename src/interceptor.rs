@@ -0,0 +1,394 @@
+// Description: Hooks run around every method of a ttRPC service
+
+use crate::auth::PeerCred;
+use crate::types::{Metadata, RequestContext, REQUEST_ID_KEY};
+use protobuf::Message;
+use ttrpc::error::Error as TError;
+use ttrpc::error::Result as TResult;
+use ttrpc::ttrpc::{Code, Response, Status};
+use ttrpc::{MethodHandler, TtrpcContext};
+
+use slog::{info, warn};
+use std::collections::HashMap;
+use std::fmt;
+use std::mem;
+use std::os::unix::io::RawFd;
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// The method handlers of a service, keyed by "/package.Service/Method", as
+// returned by a generated "create_*" function.
+type Methods = HashMap<String, Box<dyn MethodHandler + Send + Sync>>;
+
+#[cfg(feature = "async")]
+type AsyncMethods = HashMap<String, Box<dyn ttrpc::r#async::MethodHandler + Send + Sync>>;
+
+/// A call seen by an [`Interceptor`].
+pub struct Call<'a> {
+    /// What the handler knows about the request.
+    pub ctx: &'a RequestContext,
+
+    /// The method called, as `/package.Service/Method`.
+    pub method: &'a str,
+
+    /// The serialized request message.
+    pub request: &'a [u8],
+
+    /// When the call arrived, before any interceptor ran.
+    pub started: Instant,
+}
+
+/// The outcome of a call: the serialized reply message, or the error
+/// status sent instead.
+pub type CallResult = std::result::Result<Vec<u8>, Status>;
+
+/// Hooks run around each call to the methods of the services an
+/// [`InterceptorChain`] wraps. Hooks are called within the request's
+/// logging scope, so what they log carries the request ID.
+pub trait Interceptor: Send + Sync {
+    /// Identifies the interceptor in logs.
+    fn name(&self) -> &str;
+
+    /// Called before the method's handler. Returning an error rejects the
+    /// call: neither the handler nor any later interceptor runs, and the
+    /// error is sent as the reply.
+    fn before(&self, _call: &Call) -> TResult<()> {
+        Ok(())
+    }
+
+    /// Called once the call is done, unless this interceptor's `before`
+    /// was not called or rejected the call.
+    fn after(&self, _call: &Call, _result: &CallResult) {}
+}
+
+/// An ordered list of interceptors. Their `before` hooks run in the order
+/// they were added and their `after` hooks in the reverse order, so the
+/// first added sees the whole of each call.
+#[derive(Clone, Default)]
+pub struct InterceptorChain {
+    interceptors: Vec<Arc<dyn Interceptor>>,
+}
+
+impl fmt::Debug for InterceptorChain {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.interceptors.iter().map(|i| i.name()))
+            .finish()
+    }
+}
+
+impl InterceptorChain {
+    pub fn new() -> Self {
+        InterceptorChain::default()
+    }
+
+    /// Add `interceptor` to the end of the chain.
+    pub fn with<I: Interceptor + 'static>(mut self, interceptor: I) -> Self {
+        self.push(Arc::new(interceptor));
+        self
+    }
+
+    pub(crate) fn push(&mut self, interceptor: Arc<dyn Interceptor>) {
+        self.interceptors.push(interceptor);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interceptors.is_empty()
+    }
+
+    // Run the before hooks until one rejects the call. Returns how many
+    // accepted it, and the rejection if any.
+    fn before(&self, call: &Call) -> (usize, Option<Status>) {
+        for (i, interceptor) in self.interceptors.iter().enumerate() {
+            if let Err(e) = interceptor.before(call) {
+                info!(sl!(), "call rejected";
+                    "method" => call.method,
+                    "interceptor" => interceptor.name());
+
+                return (i, Some(error_status(&e)));
+            }
+        }
+
+        (self.interceptors.len(), None)
+    }
+
+    // Run the after hooks of the first "ran" interceptors, last first.
+    fn after(&self, call: &Call, ran: usize, result: &CallResult) {
+        for interceptor in self.interceptors[..ran].iter().rev() {
+            interceptor.after(call, result);
+        }
+    }
+
+    /// Wrap each handler in `methods` (as returned by a generated sync
+    /// `create_*` function) so the interceptors run around every call.
+    pub fn wrap(&self, methods: Methods) -> Methods {
+        if self.is_empty() {
+            return methods;
+        }
+
+        methods
+            .into_iter()
+            .map(|(method, handler)| {
+                let wrapped = InterceptedMethod {
+                    method: method.clone(),
+                    handler,
+                    chain: self.clone(),
+                };
+
+                (
+                    method,
+                    Box::new(wrapped) as Box<dyn MethodHandler + Send + Sync>,
+                )
+            })
+            .collect()
+    }
+
+    /// Wrap each handler in `methods` (as returned by a generated async
+    /// `create_*` function) so the interceptors run around every call.
+    /// Requires the `async` feature.
+    #[cfg(feature = "async")]
+    pub fn wrap_async(&self, methods: AsyncMethods) -> AsyncMethods {
+        if self.is_empty() {
+            return methods;
+        }
+
+        methods
+            .into_iter()
+            .map(|(method, handler)| {
+                let wrapped = InterceptedAsyncMethod {
+                    method: method.clone(),
+                    handler,
+                    chain: self.clone(),
+                };
+
+                (
+                    method,
+                    Box::new(wrapped) as Box<dyn ttrpc::r#async::MethodHandler + Send + Sync>,
+                )
+            })
+            .collect()
+    }
+}
+
+// The status sent for an error returned by a handler or interceptor.
+fn error_status(e: &TError) -> Status {
+    match e {
+        TError::RpcStatus(s) => s.clone(),
+        e => ttrpc::get_status(Code::UNKNOWN, format!("{:?}", e)),
+    }
+}
+
+// The outcome of a call, from the serialized ttRPC response sent for it.
+fn reply_result(reply: &[u8]) -> CallResult {
+    match Response::parse_from_bytes(reply) {
+        Ok(res) if res.has_status() && res.get_status().get_code() != Code::OK => {
+            Err(res.get_status().clone())
+        }
+        Ok(res) => Ok(res.get_payload().to_vec()),
+        Err(e) => Err(ttrpc::get_status(
+            Code::INTERNAL,
+            format!("failed to decode reply: {:?}", e),
+        )),
+    }
+}
+
+// The request's context, recording its ID in "metadata" so the handler
+// logs the same one, even if the server had to make it up.
+fn request_context(fd: RawFd, metadata: &mut Metadata) -> RequestContext {
+    let ctx = RequestContext::new(fd, metadata.clone());
+
+    metadata.insert(REQUEST_ID_KEY.to_string(), vec![ctx.request_id.clone()]);
+
+    ctx
+}
+
+// A sync method handler run within an interceptor chain.
+struct InterceptedMethod {
+    method: String,
+    handler: Box<dyn MethodHandler + Send + Sync>,
+    chain: InterceptorChain,
+}
+
+impl MethodHandler for InterceptedMethod {
+    fn handler(&self, mut ctx: TtrpcContext, req: ttrpc::Request) -> TResult<()> {
+        let rctx = request_context(ctx.fd, &mut ctx.metadata);
+
+        let request = req.get_payload().to_vec();
+
+        let call = Call {
+            ctx: &rctx,
+            method: &self.method,
+            request: &request,
+            started: Instant::now(),
+        };
+
+        let (ran, rejected) = rctx.scope(|| self.chain.before(&call));
+
+        if let Some(status) = rejected {
+            rctx.scope(|| self.chain.after(&call, ran, &Err(status.clone())));
+
+            let mut res = Response::new();
+
+            res.set_status(status);
+
+            return ttrpc::response_to_channel(ctx.mh.stream_id, res, ctx.res_tx);
+        }
+
+        // Have the handler send its reply here, so the interceptors see it
+        // before it is passed on.
+        let (tx, rx) = channel();
+
+        let res_tx = mem::replace(&mut ctx.res_tx, tx);
+
+        let handled = self.handler.handler(ctx, req);
+
+        let reply = rx.try_recv().ok();
+
+        let result = match (&handled, &reply) {
+            (Err(e), _) => Err(error_status(e)),
+            (Ok(()), Some((_, buf))) => reply_result(buf),
+            (Ok(()), None) => Err(ttrpc::get_status(
+                Code::INTERNAL,
+                "handler sent no reply".to_string(),
+            )),
+        };
+
+        rctx.scope(|| self.chain.after(&call, ran, &result));
+
+        if let Some(reply) = reply {
+            if let Err(e) = res_tx.send(reply) {
+                return Err(TError::Others(format!("failed to send reply: {}", e)));
+            }
+        }
+
+        handled
+    }
+}
+
+// An async method handler run within an interceptor chain.
+#[cfg(feature = "async")]
+struct InterceptedAsyncMethod {
+    method: String,
+    handler: Box<dyn ttrpc::r#async::MethodHandler + Send + Sync>,
+    chain: InterceptorChain,
+}
+
+#[cfg(feature = "async")]
+#[async_trait::async_trait]
+impl ttrpc::r#async::MethodHandler for InterceptedAsyncMethod {
+    async fn handler(
+        &self,
+        mut ctx: ttrpc::r#async::TtrpcContext,
+        req: ttrpc::Request,
+    ) -> TResult<(u32, Vec<u8>)> {
+        let rctx = request_context(ctx.fd, &mut ctx.metadata);
+
+        let request = req.get_payload().to_vec();
+
+        let call = Call {
+            ctx: &rctx,
+            method: &self.method,
+            request: &request,
+            started: Instant::now(),
+        };
+
+        let (ran, rejected) = rctx.scope(|| self.chain.before(&call));
+
+        let handled = match rejected {
+            Some(status) => {
+                let mut res = Response::new();
+
+                res.set_status(status);
+
+                match res.write_to_bytes() {
+                    Ok(buf) => Ok((ctx.mh.stream_id, buf)),
+                    Err(e) => Err(TError::Others(format!("failed to encode reply: {:?}", e))),
+                }
+            }
+            None => self.handler.handler(ctx, req).await,
+        };
+
+        let result = match &handled {
+            Ok((_, buf)) => reply_result(buf),
+            Err(e) => Err(error_status(e)),
+        };
+
+        rctx.scope(|| self.chain.after(&call, ran, &result));
+
+        handled
+    }
+}
+
+/// Logs each call once it is done: the method, the peer, the sizes of the
+/// request and reply, and the status code.
+#[derive(Debug, Default)]
+pub struct AccessLog;
+
+impl Interceptor for AccessLog {
+    fn name(&self) -> &str {
+        "access-log"
+    }
+
+    fn after(&self, call: &Call, result: &CallResult) {
+        let peer = match PeerCred::from_fd(call.ctx.fd) {
+            Some(p) => p.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        let (code, reply_len) = match result {
+            Ok(reply) => (Code::OK, reply.len()),
+            Err(status) => (status.get_code(), 0),
+        };
+
+        info!(sl!(), "access";
+            "method" => call.method,
+            "peer" => peer,
+            "request-bytes" => call.request.len(),
+            "reply-bytes" => reply_len,
+            "code" => format!("{:?}", code));
+    }
+}
+
+/// Logs how long each call took, as a warning if it took at least the
+/// slow threshold.
+#[derive(Debug, Default)]
+pub struct Timing {
+    slow_threshold: Option<Duration>,
+}
+
+impl Timing {
+    pub fn new() -> Self {
+        Timing::default()
+    }
+
+    /// Warn about calls taking at least `threshold`.
+    pub fn slow_threshold(mut self, threshold: Duration) -> Self {
+        self.slow_threshold = Some(threshold);
+        self
+    }
+}
+
+impl Interceptor for Timing {
+    fn name(&self) -> &str {
+        "timing"
+    }
+
+    fn after(&self, call: &Call, result: &CallResult) {
+        let elapsed = call.started.elapsed();
+
+        let ok = result.is_ok();
+
+        match self.slow_threshold {
+            Some(threshold) if elapsed >= threshold => warn!(sl!(), "slow call";
+                "method" => call.method,
+                "elapsed" => format!("{:?}", elapsed),
+                "threshold" => format!("{:?}", threshold),
+                "ok" => ok),
+            _ => info!(sl!(), "call timing";
+                "method" => call.method,
+                "elapsed" => format!("{:?}", elapsed),
+                "ok" => ok),
+        }
+    }
+}
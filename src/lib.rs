@@ -0,0 +1,72 @@
+// Description: Noddy ttRPC client/server library
+
+//! Reusable ttRPC client and server for `MyService`.
+//!
+//! ```no_run
+//! use test_protobuf_ttrpc::{ClientBuilder, ServerBuilder};
+//!
+//! let server = ServerBuilder::new()
+//!     .server_uri("unix:///tmp/my.socket")
+//!     .build()?;
+//!
+//! let client = ClientBuilder::new()
+//!     .server_uri("unix:///tmp/my.socket")
+//!     .build()?;
+//! # Ok::<(), test_protobuf_ttrpc::Error>(())
+//! ```
+
+#[macro_use]
+pub mod logging;
+#[cfg(feature = "async")]
+mod async_client;
+#[cfg(feature = "async")]
+mod async_server;
+pub mod auth;
+mod client;
+mod dynamic;
+pub mod error;
+mod healthcheck;
+mod interceptor;
+mod proxy;
+mod reflect;
+mod server;
+pub mod signals;
+mod socket;
+pub mod systemd;
+mod types;
+pub mod uri;
+pub mod utils;
+
+// Import the auto-generated modules
+pub mod health;
+pub mod health_ttrpc;
+#[cfg(feature = "async")]
+pub mod health_ttrpc_async;
+pub mod reflection;
+pub mod reflection_ttrpc;
+#[cfg(feature = "async")]
+pub mod reflection_ttrpc_async;
+pub mod service;
+pub mod service_ttrpc;
+#[cfg(feature = "async")]
+pub mod service_ttrpc_async;
+
+#[cfg(feature = "async")]
+pub use crate::async_client::AsyncHelloClient;
+pub use crate::auth::{AccessPolicy, AccessRule, PeerCred};
+pub use crate::client::{parse_metadata_arg, ClientBuilder, HelloClient};
+pub use crate::dynamic::{DescriptorPool, MethodInfo};
+pub use crate::error::{Error, Result};
+pub use crate::healthcheck::{HealthService, HEALTH_SERVICE_NAME, MY_SERVICE_NAME};
+pub use crate::interceptor::{AccessLog, Call, CallResult, Interceptor, InterceptorChain, Timing};
+pub use crate::proxy::{Proxy, ProxyBuilder};
+pub use crate::reflect::{ReflectionService, REFLECTION_SERVICE_NAME};
+pub use crate::server::{
+    HelloServer, ServerBuilder, DEFAULT_SHUTDOWN_GRACE_PERIOD, GREETING_LANGUAGE_KEY,
+};
+pub use crate::socket::{AbstractNaming, ABSTRACT_NAMING_NAMES};
+pub use crate::types::{
+    Config, HelloService, Metadata, RequestContext, RetryPolicy, ShutdownParams, ShutdownTrigger,
+    REQUEST_ID_KEY,
+};
+pub use crate::uri::{ServerUri, VMADDR_CID_LOCAL};
@@ -7,19 +7,12 @@
 use clap::{App, Arg, SubCommand};
 use std::io;
 use std::process::exit;
-
-#[macro_use]
-mod logging;
-mod client;
-mod server;
-mod ttrpc;
-mod types;
-
-// Import the auto-generated modules
-mod service;
-mod service_ttrpc;
-
-pub type Result<T> = std::result::Result<T, String>;
+use test_protobuf_ttrpc::error::exit_code;
+use test_protobuf_ttrpc::utils::parse_duration;
+use test_protobuf_ttrpc::{
+    logging, parse_metadata_arg, signals, AbstractNaming, AccessLog, AccessPolicy, ClientBuilder,
+    Error, ProxyBuilder, Result, RetryPolicy, ServerBuilder, Timing, ABSTRACT_NAMING_NAMES,
+};
 
 // XXX: Should really set from makefile
 const VERSION: &str = "0.0.1";
@@ -34,14 +27,48 @@ Examples:
 
 - Server:
 
-  - Unix socket:
+  - Named Unix socket:
 
     $ {program} --server-uri {unix_uri:?} server
 
+  - Abstract Unix socket:
+
+    $ {program} --server-uri {unix_uri:?} --abstract server
+
   - VSOCK socket:
 
     $ {program} --server-uri {unix_uri:?} server
 
+  - VSOCK socket on a port chosen by the kernel, which is written to a
+    file:
+
+    $ {program} --server-uri "vsock://-1:any" server --port-file /tmp/port
+
+  - TCP socket (loopback only, unless --tcp-allow-remote is given):
+
+    $ {program} --server-uri "tcp://127.0.0.1:1024" server
+
+  - VSOCK socket reachable only from this host (requires the
+    "vsock_loopback" kernel module):
+
+    $ {program} --server-uri "vsock://1:1024" server
+
+  - VSOCK socket for the host and a Unix socket for local tools, served
+    together and shut down together:
+
+    $ {program} --server-uri {vsock_uri:?} --server-uri {unix_uri:?} server
+
+  - Only allow root to shut the server down (other methods are open to
+    anyone who can connect):
+
+    $ {program} --server-uri {unix_uri:?} server --allow "Shutdown=uid:0"
+
+  - Socket activated by systemd (or "systemd-socket-activate"), which
+    passes the listening socket so no server URI is needed. If
+    NOTIFY_SOCKET is set, "READY=1" and "STOPPING=1" are sent to it:
+
+    $ systemd-socket-activate -l /tmp/my.socket {program} server
+
 - Clients:
 
   - Abstract Unix socket:
@@ -86,13 +113,142 @@ Examples:
 
       $ {program} --server-uri {vsock_uri:?} --interactive client
 
+  - Ask the server to shut down, giving a reason and letting in-flight
+    requests take up to 10 seconds to finish ("force" stops without
+    waiting for them):
+
+    $ {program} --server-uri {unix_uri:?} client \
+        --commands "Shutdown grace=10s upgrading"
+
+  - Health checks (modelled on grpc.health.v1), exiting with status 11
+    if the service is not serving. "HealthWatch" waits for the status to
+    change, for example to notice the server shutting down:
+
+    $ {program} --server-uri {unix_uri:?} client \
+        --commands "Health service.MyService"
+
+    $ {program} --server-uri {unix_uri:?} client --commands HealthWatch
+
+  - List the services and methods the server supports (logged one
+    service per line), without needing its ".proto" files:
+
+    $ {program} --server-uri {unix_uri:?} client --commands ListServices
+
+  - Call any method by name with a JSON request, printing the JSON reply.
+    Methods of other services can be called given their descriptors:
+
+    $ {program} --server-uri {unix_uri:?} client \
+        --commands 'Call service.MyService/SayHello {{"name": "foo"}}'
+
+    $ protoc --include_imports --descriptor_set_out=/tmp/other.pb other.proto
+    $ {program} --server-uri {unix_uri:?} client \
+        --descriptor-set /tmp/other.pb \
+        --commands 'Call other.OtherService/DoIt {{"count": 3}}'
+
+  - Send request metadata, which the server logs. It greets in the
+    language given by "greeting-language" (en, de, es or fr):
+
+    $ {program} --server-uri {unix_uri:?} client \
+        --metadata greeting-language=fr --commands "SayHello foo"
+
+    $ {program} --server-uri {unix_uri:?} client \
+        --commands "set-header greeting-language=de" --commands "SayHello foo"
+
+    Each command's requests carry a "request-id", logged by the client
+    and the server, which is made up per command unless set as metadata:
+
+    $ {program} --server-uri {unix_uri:?} client \
+        --metadata request-id=my-request --commands "SayHello foo"
+
+  - Log each call the server handles, and warn about slow ones:
+
+    $ {program} --server-uri {unix_uri:?} server --access-log --slow-call 100ms
+
+  - Async (tokio) client and server, when built with "--features async".
+    The client takes the same commands as the sync one. The server only
+    listens on Unix and vsock sockets:
+
+    $ {program} --server-uri {unix_uri:?} server --async
+    $ {program} --server-uri {unix_uri:?} client --async \
+        --commands "SayHello foo"
+
+  - Hybrid VSOCK socket (Firecracker / Cloud Hypervisor), connecting to
+    guest port 1024 via the VMM's host Unix socket:
+
+      $ {program} --server-uri "hvsock:///tmp/firecracker.vsock:1024" client \
+          --commands "SayHello foo"
+
+  - Waiting up to 30 seconds for a booting server:
+
+      $ {program} --server-uri {vsock_uri:?} client \
+          --connect-attempts 100 \
+          --connect-backoff 100ms \
+          --connect-max-backoff 2s \
+          --connect-timeout 30s \
+          --commands "SayHello foo"
+
+  - Deadlines (global, with a per-command override):
+
+      $ {program} --server-uri {unix_uri:?} client \
+          --timeout 5s \
+          --commands "SayHello foo" \
+          --commands "SayHello@500ms bar"
+
+- Proxy:
+
+  - Let host tools that only speak Unix sockets reach a server in a guest:
+
+    $ {program} proxy --listen {unix_uri:?} --forward "vsock://3:1024"
+
+Exit codes:
+
+  0 - Success (for a server, stopped by a Shutdown request).
+  2 - Invalid arguments or client command.
+  3 - Malformed server URI.
+  4 - Failed to create or bind a socket.
+  5 - Failed to connect to the server.
+  6 - VSOCK error.
+  7 - ttRPC transport error.
+  8 - Server returned a non-OK RPC status.
+  9 - RPC deadline exceeded.
+ 10 - Failed to read or write a file.
+ 11 - Health check reported the service is not serving.
+ 12 - Server stopped other than by a Shutdown request or a signal.
+
+ A server stopped by SIGTERM or SIGINT exits with 128 plus the signal
+ number (143 or 130), and a second signal while it is shutting down
+ exits immediately with the same code.
+
     "#,
         program = program_name,
         unix_uri = UNIX_URI,
         vsock_uri = VSOCK_URI,
     );
 }
-fn real_main() -> Result<()> {
+
+// Run the client commands (or prompt for them) using the async client.
+#[cfg(feature = "async")]
+fn run_async_client(builder: ClientBuilder, interactive: bool, commands: &[&str]) -> Result<()> {
+    let mut runtime = test_protobuf_ttrpc::utils::new_runtime()?;
+
+    runtime.block_on(async {
+        let client = builder.build_async()?;
+
+        match interactive {
+            true => client.run_interactive().await,
+            false => client.run_commands(commands).await,
+        }
+    })
+}
+
+#[cfg(not(feature = "async"))]
+fn run_async_client(_builder: ClientBuilder, _interactive: bool, _commands: &[&str]) -> Result<()> {
+    Err(Error::Usage(
+        "async client requires building with the \"async\" feature".to_string(),
+    ))
+}
+
+fn real_main() -> Result<i32> {
     let name = module_path!();
 
     let vsock_crate_names = &["vsock", "nix"];
@@ -105,6 +261,14 @@ fn real_main() -> Result<()> {
                 .long("abstract")
                 .help("Force use of an abstract socket"),
         )
+        .arg(
+            Arg::with_name("abstract-naming")
+                .long("abstract-naming")
+                .takes_value(true)
+                .possible_values(ABSTRACT_NAMING_NAMES)
+                .default_value("ttrpc-nul")
+                .help("Abstract socket name encoding: 'ttrpc-nul' (trailing NUL, as ttrpc uses), 'exact' (as C and Go use) or 'auto' (both)"),
+        )
         .arg(
             Arg::with_name("interactive")
                 .short("i")
@@ -114,8 +278,10 @@ fn real_main() -> Result<()> {
         .arg(
             Arg::with_name("server-uri")
                 .long("server-uri")
-                .help("server URI to use (unix:///some/where or vsock://cid:port)")
+                .help("server URI to use (unix:///some/where, unix://@name, vsock://cid:port, hvsock:///some/where:port or tcp://host:port). May be repeated for a server to listen on several URIs")
                 .takes_value(true)
+                .multiple(true)
+                .number_of_values(1)
                 .value_name("server-uri"),
         )
         .subcommand(
@@ -135,76 +301,400 @@ fn real_main() -> Result<()> {
                         .possible_values(vsock_crate_names)
                         .default_value("vsock")
                         .help("Specify which crate to use for vsock client comms"),
+                )
+                .arg(
+                    Arg::with_name("timeout")
+                        .long("timeout")
+                        .takes_value(true)
+                        .value_name("duration")
+                        .help("RPC deadline, for example 500ms or 5s (0 waits forever). Override per command with 'Command@duration'"),
+                )
+                .arg(
+                    Arg::with_name("connect-attempts")
+                        .long("connect-attempts")
+                        .takes_value(true)
+                        .value_name("count")
+                        .default_value("1")
+                        .help("Maximum number of times to try connecting to the server"),
+                )
+                .arg(
+                    Arg::with_name("connect-backoff")
+                        .long("connect-backoff")
+                        .takes_value(true)
+                        .value_name("duration")
+                        .default_value("100ms")
+                        .help("Delay before the first connection retry (doubles on each retry)"),
+                )
+                .arg(
+                    Arg::with_name("connect-max-backoff")
+                        .long("connect-max-backoff")
+                        .takes_value(true)
+                        .value_name("duration")
+                        .default_value("5s")
+                        .help("Maximum delay between connection retries"),
+                )
+                .arg(
+                    Arg::with_name("connect-timeout")
+                        .long("connect-timeout")
+                        .takes_value(true)
+                        .value_name("duration")
+                        .help("Stop retrying the connection after this long"),
+                )
+                .arg(
+                    Arg::with_name("descriptor-set")
+                        .long("descriptor-set")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("path")
+                        .help("FileDescriptorSet describing more services for the 'Call' command (from 'protoc --include_imports --descriptor_set_out'). May be repeated"),
+                )
+                .arg(
+                    Arg::with_name("metadata")
+                        .long("metadata")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("key=value")
+                        .help("Metadata to send with every request (change it with the 'set-header' command). May be repeated"),
+                )
+                .arg(
+                    Arg::with_name("async")
+                        .long("async")
+                        .help("Use the async (tokio) ttRPC client, which takes the same commands (needs the 'async' feature)"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("server")
+                .about("Create a ttRPC server")
+                .arg(
+                    Arg::with_name("port-file")
+                        .long("port-file")
+                        .takes_value(true)
+                        .value_name("path")
+                        .help("Write the bound vsock or TCP port to this file (useful with 'vsock://cid:any' or 'tcp://host:0')"),
+                )
+                .arg(
+                    Arg::with_name("allow")
+                        .long("allow")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .value_name("method=uid:N,gid:N")
+                        .help("Only allow Unix socket peers with these IDs to call the method (for example 'Shutdown=uid:0'). May be repeated"),
+                )
+                .arg(
+                    Arg::with_name("tcp-allow-remote")
+                        .long("tcp-allow-remote")
+                        .help("Allow a TCP server to listen on non-loopback addresses (ttRPC is unauthenticated and unencrypted)"),
+                )
+                .arg(
+                    Arg::with_name("shutdown-grace-period")
+                        .long("shutdown-grace-period")
+                        .takes_value(true)
+                        .value_name("duration")
+                        .help("How long to let in-flight requests finish on shutdown, unless the request says otherwise (default: 5s)"),
+                )
+                .arg(
+                    Arg::with_name("async")
+                        .long("async")
+                        .help("Use the async (tokio) ttRPC server, which supports Unix and vsock sockets only (needs the 'async' feature)"),
+                )
+                .arg(
+                    Arg::with_name("access-log")
+                        .long("access-log")
+                        .help("Log each call once done, with its peer, request and reply sizes and status code"),
+                )
+                .arg(
+                    Arg::with_name("log-timing")
+                        .long("log-timing")
+                        .help("Log how long each call took"),
+                )
+                .arg(
+                    Arg::with_name("slow-call")
+                        .long("slow-call")
+                        .takes_value(true)
+                        .value_name("duration")
+                        .help("Warn about calls taking at least this long (implies --log-timing)"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("proxy")
+                .about("Relay connections from one URI to another")
+                .arg(
+                    Arg::with_name("listen")
+                        .long("listen")
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("uri")
+                        .help("URI to accept connections on"),
+                )
+                .arg(
+                    Arg::with_name("forward")
+                        .long("forward")
+                        .takes_value(true)
+                        .required(true)
+                        .value_name("uri")
+                        .help("URI to relay each connection to"),
+                )
+                .arg(
+                    Arg::with_name("crate-for-vsock")
+                        .long("crate-for-vsock")
+                        .takes_value(true)
+                        .possible_values(vsock_crate_names)
+                        .default_value("vsock")
+                        .help("Specify which crate to use for vsock forwarding"),
+                )
+                .arg(
+                    Arg::with_name("tcp-allow-remote")
+                        .long("tcp-allow-remote")
+                        .help("Allow listening on non-loopback TCP addresses (ttRPC is unauthenticated and unencrypted)"),
                 ),
         )
-        .subcommand(SubCommand::with_name("server").about("Create a ttRPC server"))
         .subcommand(SubCommand::with_name("help").about("Show examples"));
 
     let args = app.get_matches();
 
     let mut server: bool = false;
 
+    let mut proxy = None;
+
     let mut use_vsock_crate_for_vsock = false;
 
     let interactive = args.is_present("interactive");
     let abstract_socket = args.is_present("abstract");
 
+    let abstract_naming: AbstractNaming = match args.value_of("abstract-naming") {
+        Some(n) => n.parse()?,
+        None => AbstractNaming::default(),
+    };
+
     let mut commands: Vec<&str> = Vec::new();
 
+    let mut timeout = None;
+
+    let mut port_file = None;
+
+    let mut allow_remote_tcp = false;
+
+    let mut access_policy = AccessPolicy::new();
+
+    let mut shutdown_grace_period = None;
+
+    let mut access_log = false;
+
+    let mut timing = None;
+
+    let mut retry_policy = RetryPolicy::default();
+
+    let mut descriptor_sets = Vec::new();
+
+    let mut metadata = Vec::new();
+
+    let mut use_async = false;
+
     if let Some(args) = args.subcommand_matches("client") {
         if !interactive {
             commands = match args.values_of("commands") {
                 Some(c) => c.collect(),
-                None => return Err("need commands to send to server".to_string()),
+                None => return Err(Error::Usage("need commands to send to server".to_string())),
             };
         }
 
+        if let Some(t) = args.value_of("timeout") {
+            timeout = Some(parse_duration(t)?);
+        }
+
+        if let Some(n) = args.value_of("connect-attempts") {
+            retry_policy.max_attempts = match n.parse::<u32>() {
+                Ok(n) if n > 0 => n,
+                _ => {
+                    return Err(Error::Usage(format!(
+                        "connect attempts must be a positive number: {:?}",
+                        n
+                    )))
+                }
+            };
+        }
+
+        if let Some(d) = args.value_of("connect-backoff") {
+            retry_policy.initial_backoff = parse_duration(d)?;
+        }
+
+        if let Some(d) = args.value_of("connect-max-backoff") {
+            retry_policy.max_backoff = parse_duration(d)?;
+        }
+
+        if let Some(d) = args.value_of("connect-timeout") {
+            retry_policy.deadline = Some(parse_duration(d)?);
+        }
+
+        if let Some(paths) = args.values_of("descriptor-set") {
+            descriptor_sets = paths.collect();
+        }
+
+        if let Some(values) = args.values_of("metadata") {
+            metadata = values
+                .map(parse_metadata_arg)
+                .collect::<Result<Vec<(&str, &str)>>>()?;
+        }
+
+        use_async = args.is_present("async");
+
         use_vsock_crate_for_vsock = match args.value_of("crate-for-vsock") {
             Some("vsock") => true,
             _ => false,
         };
-    } else if let Some(_) = args.subcommand_matches("server") {
+    } else if let Some(args) = args.subcommand_matches("server") {
         server = true;
+
+        port_file = args.value_of("port-file");
+
+        if let Some(specs) = args.values_of("allow") {
+            for spec in specs {
+                access_policy = access_policy.allow_spec(spec)?;
+            }
+        }
+
+        allow_remote_tcp = args.is_present("tcp-allow-remote");
+
+        if let Some(d) = args.value_of("shutdown-grace-period") {
+            shutdown_grace_period = Some(parse_duration(d)?);
+        }
+
+        access_log = args.is_present("access-log");
+
+        if let Some(d) = args.value_of("slow-call") {
+            timing = Some(Timing::new().slow_threshold(parse_duration(d)?));
+        } else if args.is_present("log-timing") {
+            timing = Some(Timing::new());
+        }
+
+        use_async = args.is_present("async");
+    } else if let Some(args) = args.subcommand_matches("proxy") {
+        // Both are required, so clap has already checked they are set.
+        proxy = Some((
+            args.value_of("listen").unwrap_or_default(),
+            args.value_of("forward").unwrap_or_default(),
+        ));
+
+        use_vsock_crate_for_vsock = args.value_of("crate-for-vsock") == Some("vsock");
+
+        allow_remote_tcp = args.is_present("tcp-allow-remote");
     } else if let Some(_) = args.subcommand_matches("help") {
         show_usage_examples(name);
-        return Ok(());
+        return Ok(exit_code::SUCCESS);
     } else {
-        return Err("invalid sub-command".to_string());
+        return Err(Error::Usage("invalid sub-command".to_string()));
     }
 
-    let server_uri = match args.value_of("server-uri") {
-        Some(host) => host,
-        None => return Err("need server URI".to_string()),
-    };
+    // Before the logger starts its thread, so only the server's signal
+    // handling thread sees them.
+    if server {
+        signals::block()?;
+    }
 
     let writer = io::stdout();
     let logger = logging::create_logger(name, writer);
 
-    let result = ttrpc::run_ttrpc(
-        &logger,
-        server_uri,
-        server,
-        interactive,
-        commands,
-        abstract_socket,
-        use_vsock_crate_for_vsock,
-    );
-    if result.is_err() {
-        eprintln!("error: {:?}", result.err());
-        exit(1);
+    if let Some((listen_uri, forward_uri)) = proxy {
+        return ProxyBuilder::new()
+            .listen_uri(listen_uri)
+            .forward_uri(forward_uri)
+            .abstract_socket(abstract_socket)
+            .abstract_naming(abstract_naming)
+            .allow_remote_tcp(allow_remote_tcp)
+            .use_vsock_crate(use_vsock_crate_for_vsock)
+            .logger(&logger)
+            .build()
+            .and_then(|p| p.run())
+            .map(|_| exit_code::SUCCESS);
     }
 
-    println!("result: no error: '{:?}'", result.ok());
+    // Checked by the builders, since a socket activated server does not
+    // need one.
+    let server_uris: Vec<&str> = match args.values_of("server-uri") {
+        Some(v) => v.collect(),
+        None => Vec::new(),
+    };
+
+    let result = if server {
+        let mut builder = server_uris
+            .iter()
+            .fold(ServerBuilder::new(), |b, uri| b.server_uri(uri))
+            .abstract_socket(abstract_socket)
+            .abstract_naming(abstract_naming)
+            .allow_remote_tcp(allow_remote_tcp)
+            .access_policy(access_policy)
+            .handle_signals(true)
+            .use_async(use_async)
+            .logger(&logger);
+
+        if let Some(path) = port_file {
+            builder = builder.port_file(path);
+        }
+
+        if let Some(d) = shutdown_grace_period {
+            builder = builder.shutdown_grace_period(d);
+        }
+
+        if access_log {
+            builder = builder.interceptor(AccessLog);
+        }
+
+        if let Some(timing) = timing {
+            builder = builder.interceptor(timing);
+        }
+
+        builder
+            .build()
+            .and_then(|s| s.run())
+            .map(|trigger| trigger.exit_code())
+    } else {
+        if server_uris.len() > 1 {
+            return Err(Error::Usage(
+                "client accepts a single server URI".to_string(),
+            ));
+        }
+
+        let builder = descriptor_sets
+            .iter()
+            .fold(ClientBuilder::new(), |b, path| b.descriptor_set(path));
+
+        let builder = metadata
+            .iter()
+            .fold(builder, |b, (key, value)| b.metadata(key, value))
+            .server_uri(server_uris.first().unwrap_or(&""))
+            .abstract_socket(abstract_socket)
+            .abstract_naming(abstract_naming)
+            .use_vsock_crate(use_vsock_crate_for_vsock)
+            .timeout(timeout)
+            .retry_policy(retry_policy)
+            .logger(&logger);
+
+        let result = match use_async {
+            true => run_async_client(builder, interactive, &commands),
+            false => builder.build().and_then(|c| match interactive {
+                true => c.run_interactive(),
+                false => c.run_commands(&commands),
+            }),
+        };
+
+        result.map(|_| exit_code::SUCCESS)
+    };
+    let code = result?;
+
+    println!("result: no error");
 
-    Ok(())
+    Ok(code)
 }
 
 fn main() {
     match real_main() {
         Err(e) => {
             eprintln!("ERROR: {}", e);
-            exit(1);
+            exit(e.exit_code());
         }
-        _ => (),
+        Ok(code) => exit(code),
     };
 }
@@ -0,0 +1,318 @@
+// Description: Relay connections between any two supported transports
+
+use crate::auth::AccessPolicy;
+use crate::client::client_create_fd;
+use crate::error::{Error, Result};
+use crate::server::server_listen_fds;
+use crate::socket::AbstractNaming;
+use crate::types::Config;
+use crate::uri::ServerUri;
+use nix::sys::socket::{accept4, shutdown, Shutdown, SockFlag};
+use nix::unistd::close;
+
+use slog::{error, info, o, Logger};
+use slog_scope::GlobalLoggerGuard;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::channel;
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+const RELAY_BUF_SIZE: usize = 64 * 1024;
+
+// Copy bytes from "from" to "to" until end of file, returning the number
+// of bytes copied and the error that stopped the copy, if any.
+//
+// On end of file only the write side of "to" is shut down, so the peer
+// sees end of file but can still reply. On error both sockets are shut
+// down to unblock the relay running in the other direction.
+fn relay(mut from: File, mut to: File) -> (u64, Option<io::Error>) {
+    let mut buf = vec![0u8; RELAY_BUF_SIZE];
+    let mut total: u64 = 0;
+
+    let err = loop {
+        let n = match from.read(&mut buf) {
+            Ok(0) => break None,
+            Ok(n) => n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => break Some(e),
+        };
+
+        if let Err(e) = to.write_all(&buf[..n]) {
+            break Some(e);
+        }
+
+        total += n as u64;
+    };
+
+    match err {
+        None => {
+            let _ = shutdown(to.as_raw_fd(), Shutdown::Write);
+        }
+        Some(_) => {
+            let _ = shutdown(from.as_raw_fd(), Shutdown::Both);
+            let _ = shutdown(to.as_raw_fd(), Shutdown::Both);
+        }
+    }
+
+    (total, err)
+}
+
+fn format_relay_error(err: &Option<io::Error>) -> String {
+    match err {
+        Some(e) => format!("{:?}", e),
+        None => "".to_string(),
+    }
+}
+
+// Relay bytes in both directions between the accepted connection "conn_fd"
+// and a new connection to "forward_uri" until both sides are done.
+fn proxy_connection(id: usize, conn_fd: RawFd, forward_uri: &ServerUri, cfg: &Config) {
+    let forward_fd = match client_create_fd(
+        forward_uri,
+        cfg.abstract_naming,
+        cfg.use_vsock_crate_for_vsock,
+    ) {
+        Ok(fd) => fd,
+        Err(e) => {
+            error!(sl!(), "failed to connect to forward URI";
+                "connection" => id,
+                "forward-uri" => forward_uri.to_string(),
+                "error" => format!("{}", e));
+
+            let _ = close(conn_fd);
+            return;
+        }
+    };
+
+    info!(sl!(), "relaying connection";
+        "connection" => id,
+        "forward-uri" => forward_uri.to_string());
+
+    // The files take ownership of the descriptors, which are closed when
+    // both relays finish.
+    let conn = unsafe { File::from_raw_fd(conn_fd) };
+    let forward = unsafe { File::from_raw_fd(forward_fd) };
+
+    let (conn_reader, forward_reader) = match (conn.try_clone(), forward.try_clone()) {
+        (Ok(c), Ok(f)) => (c, f),
+        (Err(e), _) | (_, Err(e)) => {
+            error!(sl!(), "failed to duplicate connection";
+                "connection" => id,
+                "error" => format!("{:?}", e));
+            return;
+        }
+    };
+
+    let upstream = thread::spawn(move || relay(conn_reader, forward));
+
+    let (bytes_to_client, downstream_err) = relay(forward_reader, conn);
+
+    let (bytes_to_forward, upstream_err) = match upstream.join() {
+        Ok(result) => result,
+        Err(_) => {
+            error!(sl!(), "relay thread panicked"; "connection" => id);
+            (0, None)
+        }
+    };
+
+    info!(sl!(), "connection closed";
+        "connection" => id,
+        "bytes-to-forward" => bytes_to_forward,
+        "bytes-to-client" => bytes_to_client,
+        "upstream-error" => format_relay_error(&upstream_err),
+        "downstream-error" => format_relay_error(&downstream_err));
+}
+
+// Accept connections on "fd" forever, relaying each one on its own thread.
+fn accept_loop(
+    fd: RawFd,
+    forward_uri: Arc<ServerUri>,
+    cfg: Arc<Config>,
+    next_id: Arc<AtomicUsize>,
+) -> Result<()> {
+    loop {
+        let conn_fd = match accept4(fd, SockFlag::SOCK_CLOEXEC) {
+            Ok(fd) => fd,
+            Err(nix::Error::Sys(nix::errno::Errno::EINTR)) => continue,
+            Err(e) => return Err(Error::Socket(format!("failed to accept: {:?}", e))),
+        };
+
+        let id = next_id.fetch_add(1, Ordering::SeqCst);
+
+        info!(sl!(), "accepted connection"; "connection" => id);
+
+        let forward_uri = forward_uri.clone();
+        let cfg = cfg.clone();
+
+        thread::spawn(move || proxy_connection(id, conn_fd, &forward_uri, &cfg));
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ProxyBuilder {
+    listen_uri: String,
+    forward_uri: String,
+    force_abstract_socket: bool,
+    abstract_naming: AbstractNaming,
+    allow_remote_tcp: bool,
+    use_vsock_crate_for_vsock: bool,
+    logger: Option<Logger>,
+}
+
+impl ProxyBuilder {
+    pub fn new() -> Self {
+        ProxyBuilder {
+            use_vsock_crate_for_vsock: true,
+            ..Default::default()
+        }
+    }
+
+    /// URI to accept connections on. Any scheme the server can listen on
+    /// is supported.
+    pub fn listen_uri(mut self, listen_uri: &str) -> Self {
+        self.listen_uri = listen_uri.to_string();
+        self
+    }
+
+    /// URI to relay each accepted connection to. Any scheme the client can
+    /// connect to is supported.
+    pub fn forward_uri(mut self, forward_uri: &str) -> Self {
+        self.forward_uri = forward_uri.to_string();
+        self
+    }
+
+    /// Treat Unix socket paths in both URIs as abstract, even without an
+    /// `@` prefix.
+    pub fn abstract_socket(mut self, force_abstract_socket: bool) -> Self {
+        self.force_abstract_socket = force_abstract_socket;
+        self
+    }
+
+    /// How abstract socket names are encoded. `Auto` listens on both names
+    /// and tries each name when forwarding.
+    pub fn abstract_naming(mut self, abstract_naming: AbstractNaming) -> Self {
+        self.abstract_naming = abstract_naming;
+        self
+    }
+
+    /// Allow listening on non-loopback TCP addresses.
+    pub fn allow_remote_tcp(mut self, allow_remote_tcp: bool) -> Self {
+        self.allow_remote_tcp = allow_remote_tcp;
+        self
+    }
+
+    /// Use the "vsock" crate (the default), rather than the "nix" crate,
+    /// to connect to a `vsock://` forward URI.
+    pub fn use_vsock_crate(mut self, use_vsock_crate_for_vsock: bool) -> Self {
+        self.use_vsock_crate_for_vsock = use_vsock_crate_for_vsock;
+        self
+    }
+
+    /// Install a child of `logger` as the global logger for as long as the
+    /// proxy exists.
+    pub fn logger(mut self, logger: &Logger) -> Self {
+        self.logger = Some(logger.clone());
+        self
+    }
+
+    /// Create the listening sockets. Connections are not accepted until
+    /// [`Proxy::run`] is called.
+    pub fn build(self) -> Result<Proxy> {
+        if self.listen_uri.is_empty() {
+            return Err(Error::Usage("need listen URI".to_string()));
+        }
+
+        if self.forward_uri.is_empty() {
+            return Err(Error::Usage("need forward URI".to_string()));
+        }
+
+        let log_guard = self
+            .logger
+            .map(|l| slog_scope::set_global_logger(l.new(o!("subsystem" => "proxy"))));
+
+        let mut listen_uri: ServerUri = self.listen_uri.parse()?;
+        let mut forward_uri: ServerUri = self.forward_uri.parse()?;
+
+        if self.force_abstract_socket {
+            listen_uri = listen_uri.into_abstract();
+            forward_uri = forward_uri.into_abstract();
+        }
+
+        let mut cfg = Config {
+            server_uri: listen_uri,
+            interactive: false,
+            force_abstract_socket: self.force_abstract_socket,
+            abstract_naming: self.abstract_naming,
+            allow_remote_tcp: self.allow_remote_tcp,
+            use_vsock_crate_for_vsock: self.use_vsock_crate_for_vsock,
+            access_policy: AccessPolicy::default(),
+            shutdown_grace_period: Duration::default(),
+            tx: None,
+        };
+
+        let (fds, bound_uri) = server_listen_fds(&cfg)?;
+
+        cfg.server_uri = bound_uri;
+
+        info!(sl!(), "listening";
+            "listen-uri" => cfg.server_uri.to_string(),
+            "forward-uri" => forward_uri.to_string());
+
+        Ok(Proxy {
+            cfg,
+            forward_uri,
+            fds,
+            _log_guard: log_guard,
+        })
+    }
+}
+
+/// Relays every connection accepted on the listen URI to the forward URI.
+pub struct Proxy {
+    cfg: Config,
+    forward_uri: ServerUri,
+    fds: Vec<RawFd>,
+    _log_guard: Option<GlobalLoggerGuard>,
+}
+
+impl Proxy {
+    /// The URI actually listened on, with any kernel-chosen port filled in.
+    pub fn listen_uri(&self) -> &ServerUri {
+        &self.cfg.server_uri
+    }
+
+    /// The URI each connection is relayed to.
+    pub fn forward_uri(&self) -> &ServerUri {
+        &self.forward_uri
+    }
+
+    /// Accept and relay connections until accepting fails on any of the
+    /// listening sockets.
+    pub fn run(self) -> Result<()> {
+        let forward_uri = Arc::new(self.forward_uri);
+        let cfg = Arc::new(self.cfg);
+        let next_id = Arc::new(AtomicUsize::new(0));
+
+        let (tx, rx) = channel::<Result<()>>();
+
+        for fd in self.fds {
+            let forward_uri = forward_uri.clone();
+            let cfg = cfg.clone();
+            let next_id = next_id.clone();
+            let tx = tx.clone();
+
+            thread::spawn(move || {
+                let _ = tx.send(accept_loop(fd, forward_uri, cfg, next_id));
+            });
+        }
+
+        match rx.recv() {
+            Ok(result) => result,
+            Err(_) => Err(Error::Socket("accept threads exited".to_string())),
+        }
+    }
+}
@@ -0,0 +1,219 @@
+// Description: Server reflection built on the embedded file descriptors
+
+use crate::reflection::{
+    FileContainingSymbolRequest, FileDescriptorResponse, ListServicesRequest, ListServicesResponse,
+    ServiceInfo,
+};
+use crate::reflection_ttrpc::ServerReflection;
+use crate::server::{request_context, rpc_error};
+use crate::types::RequestContext;
+use protobuf::descriptor::{DescriptorProto, FileDescriptorProto};
+use protobuf::{Message, RepeatedField};
+use ttrpc::error::Result as TResult;
+use ttrpc::ttrpc::Code;
+use ttrpc::TtrpcContext;
+
+/// Name under which the reflection service lists itself.
+pub const REFLECTION_SERVICE_NAME: &str = "ttrpc.reflection.v1.ServerReflection";
+
+const LIST_SERVICES_METHOD: &str = "ListServices";
+const FILE_CONTAINING_SYMBOL_METHOD: &str = "FileContainingSymbol";
+
+// Prefix "name" with the package, if any, to give a fully qualified name.
+fn qualify(package: &str, name: &str) -> String {
+    match package.is_empty() {
+        true => name.to_string(),
+        false => format!("{}.{}", package, name),
+    }
+}
+
+// Returns true if "symbol" names "msg" (whose fully qualified name is
+// "name"), or any message or enum nested within it.
+fn message_defines(msg: &DescriptorProto, name: &str, symbol: &str) -> bool {
+    if symbol == name {
+        return true;
+    }
+
+    if msg
+        .get_enum_type()
+        .iter()
+        .any(|e| symbol == qualify(name, e.get_name()))
+    {
+        return true;
+    }
+
+    msg.get_nested_type()
+        .iter()
+        .any(|m| message_defines(m, &qualify(name, m.get_name()), symbol))
+}
+
+/// Describes the services a server has registered, using the descriptors
+/// embedded in the generated code.
+#[derive(Debug, Clone)]
+pub struct ReflectionService {
+    files: Vec<&'static FileDescriptorProto>,
+    services: Vec<String>,
+}
+
+impl ReflectionService {
+    /// Describe the services called `services` (fully qualified, for
+    /// example "service.MyService"), which are defined in `files`.
+    pub fn new(files: &[&'static FileDescriptorProto], services: &[&str]) -> Self {
+        ReflectionService {
+            files: files.to_vec(),
+            services: services.iter().map(|s| s.to_string()).collect(),
+        }
+    }
+
+    fn is_registered(&self, file: &FileDescriptorProto, service: &str) -> bool {
+        let name = qualify(file.get_package(), service);
+
+        self.services.contains(&name)
+    }
+
+    /// The registered services, in the order their files were given.
+    pub fn services(&self) -> Vec<ServiceInfo> {
+        let mut services = Vec::new();
+
+        for file in &self.files {
+            for svc in file.get_service() {
+                if !self.is_registered(file, svc.get_name()) {
+                    continue;
+                }
+
+                let mut info = ServiceInfo::new();
+
+                info.set_name(qualify(file.get_package(), svc.get_name()));
+                info.set_file(file.get_name().to_string());
+                info.set_methods(RepeatedField::from_vec(
+                    svc.get_method()
+                        .iter()
+                        .map(|m| m.get_name().to_string())
+                        .collect(),
+                ));
+
+                services.push(info);
+            }
+        }
+
+        services
+    }
+
+    // Returns true if "file" defines "symbol" and also defines a registered
+    // service (so only the descriptors of registered services are exposed).
+    fn file_defines(&self, file: &FileDescriptorProto, symbol: &str) -> bool {
+        let package = file.get_package();
+
+        let registered: Vec<_> = file
+            .get_service()
+            .iter()
+            .filter(|s| self.is_registered(file, s.get_name()))
+            .collect();
+
+        if registered.is_empty() {
+            return false;
+        }
+
+        let is_service = registered.iter().any(|s| {
+            let name = qualify(package, s.get_name());
+
+            symbol == name
+                || s.get_method()
+                    .iter()
+                    .any(|m| symbol == qualify(&name, m.get_name()))
+        });
+
+        is_service
+            || file
+                .get_message_type()
+                .iter()
+                .any(|m| message_defines(m, &qualify(package, m.get_name()), symbol))
+            || file
+                .get_enum_type()
+                .iter()
+                .any(|e| symbol == qualify(package, e.get_name()))
+    }
+
+    /// The file defining `symbol`, if it is used by a registered service.
+    pub fn file_containing_symbol(&self, symbol: &str) -> Option<&'static FileDescriptorProto> {
+        self.files
+            .iter()
+            .find(|f| self.file_defines(f, symbol))
+            .copied()
+    }
+}
+
+// The ListServices and FileContainingSymbol handlers, shared by the sync
+// and async services, which call them within the request's logging scope
+// (see RequestContext::scope).
+impl ReflectionService {
+    pub(crate) fn handle_list_services(
+        &self,
+        ctx: &RequestContext,
+        _req: ListServicesRequest,
+    ) -> TResult<ListServicesResponse> {
+        ctx.log_request(REFLECTION_SERVICE_NAME, LIST_SERVICES_METHOD);
+
+        let mut resp = ListServicesResponse::new();
+
+        resp.set_services(RepeatedField::from_vec(self.services()));
+
+        Ok(resp)
+    }
+
+    pub(crate) fn handle_file_containing_symbol(
+        &self,
+        ctx: &RequestContext,
+        req: FileContainingSymbolRequest,
+    ) -> TResult<FileDescriptorResponse> {
+        ctx.log_request(REFLECTION_SERVICE_NAME, FILE_CONTAINING_SYMBOL_METHOD);
+
+        let file = match self.file_containing_symbol(req.get_symbol()) {
+            Some(f) => f,
+            None => {
+                return Err(rpc_error(
+                    Code::NOT_FOUND,
+                    format!("unknown symbol {:?}", req.get_symbol()),
+                ))
+            }
+        };
+
+        let bytes = match file.write_to_bytes() {
+            Ok(b) => b,
+            Err(e) => {
+                return Err(rpc_error(
+                    Code::INTERNAL,
+                    format!("failed to serialize {:?}: {:?}", file.get_name(), e),
+                ))
+            }
+        };
+
+        let mut resp = FileDescriptorResponse::new();
+
+        resp.set_file_descriptor_proto(bytes);
+
+        Ok(resp)
+    }
+}
+
+impl ServerReflection for ReflectionService {
+    fn list_services(
+        &self,
+        ctx: &TtrpcContext,
+        req: ListServicesRequest,
+    ) -> TResult<ListServicesResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_list_services(&ctx, req))
+    }
+
+    fn file_containing_symbol(
+        &self,
+        ctx: &TtrpcContext,
+        req: FileContainingSymbolRequest,
+    ) -> TResult<FileDescriptorResponse> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_file_containing_symbol(&ctx, req))
+    }
+}
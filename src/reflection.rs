@@ -0,0 +1,963 @@
+// This file is generated by rust-protobuf 2.14.0. Do not edit
+// @generated
+
+// https://github.com/rust-lang/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+//! Generated file from `reflection.proto`
+
+use protobuf::Message as Message_imported_for_functions;
+use protobuf::ProtobufEnum as ProtobufEnum_imported_for_functions;
+
+/// Generated files are compatible only with the same version
+/// of protobuf runtime.
+// const _PROTOBUF_VERSION_CHECK: () = ::protobuf::VERSION_2_14_0;
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ListServicesRequest {
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a ListServicesRequest {
+    fn default() -> &'a ListServicesRequest {
+        <ListServicesRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ListServicesRequest {
+    pub fn new() -> ListServicesRequest {
+        ::std::default::Default::default()
+    }
+}
+
+impl ::protobuf::Message for ListServicesRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ListServicesRequest {
+        ListServicesRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let fields = ::std::vec::Vec::new();
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<ListServicesRequest>(
+                    "ListServicesRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static ListServicesRequest {
+        static mut instance: ::protobuf::lazy::Lazy<ListServicesRequest> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(ListServicesRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for ListServicesRequest {
+    fn clear(&mut self) {
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ListServicesRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ListServicesRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ServiceInfo {
+    // message fields
+    pub name: ::std::string::String,
+    pub methods: ::protobuf::RepeatedField<::std::string::String>,
+    pub file: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a ServiceInfo {
+    fn default() -> &'a ServiceInfo {
+        <ServiceInfo as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ServiceInfo {
+    pub fn new() -> ServiceInfo {
+        ::std::default::Default::default()
+    }
+
+    // string name = 1;
+
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+    pub fn clear_name(&mut self) {
+        self.name.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_name(&mut self, v: ::std::string::String) {
+        self.name = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_name(&mut self) -> &mut ::std::string::String {
+        &mut self.name
+    }
+
+    // Take field
+    pub fn take_name(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.name, ::std::string::String::new())
+    }
+
+    // repeated string methods = 2;
+
+
+    pub fn get_methods(&self) -> &[::std::string::String] {
+        &self.methods
+    }
+    pub fn clear_methods(&mut self) {
+        self.methods.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_methods(&mut self, v: ::protobuf::RepeatedField<::std::string::String>) {
+        self.methods = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_methods(&mut self) -> &mut ::protobuf::RepeatedField<::std::string::String> {
+        &mut self.methods
+    }
+
+    // Take field
+    pub fn take_methods(&mut self) -> ::protobuf::RepeatedField<::std::string::String> {
+        ::std::mem::replace(&mut self.methods, ::protobuf::RepeatedField::new())
+    }
+
+    // string file = 3;
+
+
+    pub fn get_file(&self) -> &str {
+        &self.file
+    }
+    pub fn clear_file(&mut self) {
+        self.file.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_file(&mut self, v: ::std::string::String) {
+        self.file = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_file(&mut self) -> &mut ::std::string::String {
+        &mut self.file
+    }
+
+    // Take field
+    pub fn take_file(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.file, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for ServiceInfo {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.name)?;
+                },
+                2 => {
+                    ::protobuf::rt::read_repeated_string_into(wire_type, is, &mut self.methods)?;
+                },
+                3 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.file)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.name.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.name);
+        }
+        for value in &self.methods {
+            my_size += ::protobuf::rt::string_size(2, &value);
+        };
+        if !self.file.is_empty() {
+            my_size += ::protobuf::rt::string_size(3, &self.file);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.name.is_empty() {
+            os.write_string(1, &self.name)?;
+        }
+        for v in &self.methods {
+            os.write_string(2, &v)?;
+        };
+        if !self.file.is_empty() {
+            os.write_string(3, &self.file)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ServiceInfo {
+        ServiceInfo::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "name",
+                    |m: &ServiceInfo| { &m.name },
+                    |m: &mut ServiceInfo| { &mut m.name },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "methods",
+                    |m: &ServiceInfo| { &m.methods },
+                    |m: &mut ServiceInfo| { &mut m.methods },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "file",
+                    |m: &ServiceInfo| { &m.file },
+                    |m: &mut ServiceInfo| { &mut m.file },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<ServiceInfo>(
+                    "ServiceInfo",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static ServiceInfo {
+        static mut instance: ::protobuf::lazy::Lazy<ServiceInfo> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(ServiceInfo::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for ServiceInfo {
+    fn clear(&mut self) {
+        self.name.clear();
+        self.methods.clear();
+        self.file.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ServiceInfo {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ServiceInfo {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct ListServicesResponse {
+    // message fields
+    pub services: ::protobuf::RepeatedField<ServiceInfo>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a ListServicesResponse {
+    fn default() -> &'a ListServicesResponse {
+        <ListServicesResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl ListServicesResponse {
+    pub fn new() -> ListServicesResponse {
+        ::std::default::Default::default()
+    }
+
+    // repeated .ttrpc.reflection.v1.ServiceInfo services = 1;
+
+
+    pub fn get_services(&self) -> &[ServiceInfo] {
+        &self.services
+    }
+    pub fn clear_services(&mut self) {
+        self.services.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_services(&mut self, v: ::protobuf::RepeatedField<ServiceInfo>) {
+        self.services = v;
+    }
+
+    // Mutable pointer to the field.
+    pub fn mut_services(&mut self) -> &mut ::protobuf::RepeatedField<ServiceInfo> {
+        &mut self.services
+    }
+
+    // Take field
+    pub fn take_services(&mut self) -> ::protobuf::RepeatedField<ServiceInfo> {
+        ::std::mem::replace(&mut self.services, ::protobuf::RepeatedField::new())
+    }
+}
+
+impl ::protobuf::Message for ListServicesResponse {
+    fn is_initialized(&self) -> bool {
+        for v in &self.services {
+            if !v.is_initialized() {
+                return false;
+            }
+        };
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_repeated_message_into(wire_type, is, &mut self.services)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        for value in &self.services {
+            let len = value.compute_size();
+            my_size += 1 + ::protobuf::rt::compute_raw_varint32_size(len) + len;
+        };
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        for v in &self.services {
+            os.write_tag(1, ::protobuf::wire_format::WireTypeLengthDelimited)?;
+            os.write_raw_varint32(v.get_cached_size())?;
+            v.write_to_with_cached_sizes(os)?;
+        };
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> ListServicesResponse {
+        ListServicesResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_repeated_field_accessor::<_, ::protobuf::types::ProtobufTypeMessage<ServiceInfo>>(
+                    "services",
+                    |m: &ListServicesResponse| { &m.services },
+                    |m: &mut ListServicesResponse| { &mut m.services },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<ListServicesResponse>(
+                    "ListServicesResponse",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static ListServicesResponse {
+        static mut instance: ::protobuf::lazy::Lazy<ListServicesResponse> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(ListServicesResponse::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for ListServicesResponse {
+    fn clear(&mut self) {
+        self.services.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for ListServicesResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for ListServicesResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct FileContainingSymbolRequest {
+    // message fields
+    pub symbol: ::std::string::String,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a FileContainingSymbolRequest {
+    fn default() -> &'a FileContainingSymbolRequest {
+        <FileContainingSymbolRequest as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl FileContainingSymbolRequest {
+    pub fn new() -> FileContainingSymbolRequest {
+        ::std::default::Default::default()
+    }
+
+    // string symbol = 1;
+
+
+    pub fn get_symbol(&self) -> &str {
+        &self.symbol
+    }
+    pub fn clear_symbol(&mut self) {
+        self.symbol.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_symbol(&mut self, v: ::std::string::String) {
+        self.symbol = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_symbol(&mut self) -> &mut ::std::string::String {
+        &mut self.symbol
+    }
+
+    // Take field
+    pub fn take_symbol(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.symbol, ::std::string::String::new())
+    }
+}
+
+impl ::protobuf::Message for FileContainingSymbolRequest {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.symbol)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.symbol.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.symbol);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.symbol.is_empty() {
+            os.write_string(1, &self.symbol)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> FileContainingSymbolRequest {
+        FileContainingSymbolRequest::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "symbol",
+                    |m: &FileContainingSymbolRequest| { &m.symbol },
+                    |m: &mut FileContainingSymbolRequest| { &mut m.symbol },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<FileContainingSymbolRequest>(
+                    "FileContainingSymbolRequest",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static FileContainingSymbolRequest {
+        static mut instance: ::protobuf::lazy::Lazy<FileContainingSymbolRequest> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(FileContainingSymbolRequest::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for FileContainingSymbolRequest {
+    fn clear(&mut self) {
+        self.symbol.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for FileContainingSymbolRequest {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for FileContainingSymbolRequest {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+#[derive(PartialEq,Clone,Default)]
+pub struct FileDescriptorResponse {
+    // message fields
+    pub file_descriptor_proto: ::std::vec::Vec<u8>,
+    // special fields
+    pub unknown_fields: ::protobuf::UnknownFields,
+    pub cached_size: ::protobuf::CachedSize,
+}
+
+impl<'a> ::std::default::Default for &'a FileDescriptorResponse {
+    fn default() -> &'a FileDescriptorResponse {
+        <FileDescriptorResponse as ::protobuf::Message>::default_instance()
+    }
+}
+
+impl FileDescriptorResponse {
+    pub fn new() -> FileDescriptorResponse {
+        ::std::default::Default::default()
+    }
+
+    // bytes file_descriptor_proto = 1;
+
+
+    pub fn get_file_descriptor_proto(&self) -> &[u8] {
+        &self.file_descriptor_proto
+    }
+    pub fn clear_file_descriptor_proto(&mut self) {
+        self.file_descriptor_proto.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_file_descriptor_proto(&mut self, v: ::std::vec::Vec<u8>) {
+        self.file_descriptor_proto = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_file_descriptor_proto(&mut self) -> &mut ::std::vec::Vec<u8> {
+        &mut self.file_descriptor_proto
+    }
+
+    // Take field
+    pub fn take_file_descriptor_proto(&mut self) -> ::std::vec::Vec<u8> {
+        ::std::mem::replace(&mut self.file_descriptor_proto, ::std::vec::Vec::new())
+    }
+}
+
+impl ::protobuf::Message for FileDescriptorResponse {
+    fn is_initialized(&self) -> bool {
+        true
+    }
+
+    fn merge_from(&mut self, is: &mut ::protobuf::CodedInputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        while !is.eof()? {
+            let (field_number, wire_type) = is.read_tag_unpack()?;
+            match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_bytes_into(wire_type, is, &mut self.file_descriptor_proto)?;
+                },
+                _ => {
+                    ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
+                },
+            };
+        }
+        ::std::result::Result::Ok(())
+    }
+
+    // Compute sizes of nested messages
+    #[allow(unused_variables)]
+    fn compute_size(&self) -> u32 {
+        let mut my_size = 0;
+        if !self.file_descriptor_proto.is_empty() {
+            my_size += ::protobuf::rt::bytes_size(1, &self.file_descriptor_proto);
+        }
+        my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
+        self.cached_size.set(my_size);
+        my_size
+    }
+
+    fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.file_descriptor_proto.is_empty() {
+            os.write_bytes(1, &self.file_descriptor_proto)?;
+        }
+        os.write_unknown_fields(self.get_unknown_fields())?;
+        ::std::result::Result::Ok(())
+    }
+
+    fn get_cached_size(&self) -> u32 {
+        self.cached_size.get()
+    }
+
+    fn get_unknown_fields(&self) -> &::protobuf::UnknownFields {
+        &self.unknown_fields
+    }
+
+    fn mut_unknown_fields(&mut self) -> &mut ::protobuf::UnknownFields {
+        &mut self.unknown_fields
+    }
+
+    fn as_any(&self) -> &dyn (::std::any::Any) {
+        self as &dyn (::std::any::Any)
+    }
+    fn as_any_mut(&mut self) -> &mut dyn (::std::any::Any) {
+        self as &mut dyn (::std::any::Any)
+    }
+    fn into_any(self: Box<Self>) -> ::std::boxed::Box<dyn (::std::any::Any)> {
+        self
+    }
+
+    fn descriptor(&self) -> &'static ::protobuf::reflect::MessageDescriptor {
+        Self::descriptor_static()
+    }
+
+    fn new() -> FileDescriptorResponse {
+        FileDescriptorResponse::new()
+    }
+
+    fn descriptor_static() -> &'static ::protobuf::reflect::MessageDescriptor {
+        static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            descriptor.get(|| {
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBytes>(
+                    "file_descriptor_proto",
+                    |m: &FileDescriptorResponse| { &m.file_descriptor_proto },
+                    |m: &mut FileDescriptorResponse| { &mut m.file_descriptor_proto },
+                ));
+                ::protobuf::reflect::MessageDescriptor::new_pb_name::<FileDescriptorResponse>(
+                    "FileDescriptorResponse",
+                    fields,
+                    file_descriptor_proto()
+                )
+            })
+        }
+    }
+
+    fn default_instance() -> &'static FileDescriptorResponse {
+        static mut instance: ::protobuf::lazy::Lazy<FileDescriptorResponse> = ::protobuf::lazy::Lazy::INIT;
+        unsafe {
+            instance.get(FileDescriptorResponse::new)
+        }
+    }
+}
+
+impl ::protobuf::Clear for FileDescriptorResponse {
+    fn clear(&mut self) {
+        self.file_descriptor_proto.clear();
+        self.unknown_fields.clear();
+    }
+}
+
+impl ::std::fmt::Debug for FileDescriptorResponse {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        ::protobuf::text_format::fmt(self, f)
+    }
+}
+
+impl ::protobuf::reflect::ProtobufValue for FileDescriptorResponse {
+    fn as_ref(&self) -> ::protobuf::reflect::ReflectValueRef {
+        ::protobuf::reflect::ReflectValueRef::Message(self)
+    }
+}
+
+static file_descriptor_proto_data: &'static [u8] = b"\
+    \n\x10reflection.proto\x12\x13ttrpc.reflection.v1\"\x15\n\x13ListService\
+    sRequest\"O\n\x0bServiceInfo\x12\x12\n\x04name\x18\x01\x20\x01(\tR\x04na\
+    me\x12\x18\n\x07methods\x18\x02\x20\x03(\tR\x07methods\x12\x12\n\x04file\
+    \x18\x03\x20\x01(\tR\x04file\"T\n\x14ListServicesResponse\x12<\n\x08serv\
+    ices\x18\x01\x20\x03(\x0b2\x20.ttrpc.reflection.v1.ServiceInfoR\x08servi\
+    ces\"5\n\x1bFileContainingSymbolRequest\x12\x16\n\x06symbol\x18\x01\x20\
+    \x01(\tR\x06symbol\"L\n\x16FileDescriptorResponse\x122\n\x15file_descrip\
+    tor_proto\x18\x01\x20\x01(\x0cR\x13fileDescriptorProto2\xf2\x01\n\x10Ser\
+    verReflection\x12e\n\x0cListServices\x12(.ttrpc.reflection.v1.ListServic\
+    esRequest\x1a).ttrpc.reflection.v1.ListServicesResponse\"\0\x12w\n\x14Fi\
+    leContainingSymbol\x120.ttrpc.reflection.v1.FileContainingSymbolRequest\
+    \x1a+.ttrpc.reflection.v1.FileDescriptorResponse\"\0J\xb0\n\n\x06\x12\
+    \x04\0\0,\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x98\x01\n\x01\x02\x12\
+    \x03\x04\0\x1c\x1a\x8d\x01\x20Loosely\x20modelled\x20on\x20grpc.reflecti\
+    on.v1alpha.\x20ttRPC\x20has\x20no\x20streaming,\x20so\n\x20each\x20query\
+    \x20is\x20a\x20separate\x20method\x20rather\x20than\x20a\x20message\x20o\
+    n\x20a\x20stream.\n\n\n\n\x02\x04\0\x12\x04\x06\0\x07\x01\n\n\n\x03\x04\
+    \0\x01\x12\x03\x06\x08\x1b\n\n\n\x02\x04\x01\x12\x04\t\0\x12\x01\n\n\n\
+    \x03\x04\x01\x01\x12\x03\t\x08\x13\nE\n\x04\x04\x01\x02\0\x12\x03\x0b\
+    \x02\x12\x1a8\x20Fully\x20qualified\x20name,\x20for\x20example\x20\"serv\
+    ice.MyService\".\n\n\x0c\n\x05\x04\x01\x02\0\x05\x12\x03\x0b\x02\x08\n\
+    \x0c\n\x05\x04\x01\x02\0\x01\x12\x03\x0b\t\r\n\x0c\n\x05\x04\x01\x02\0\
+    \x03\x12\x03\x0b\x10\x11\n4\n\x04\x04\x01\x02\x01\x12\x03\x0e\x02\x1e\
+    \x1a'\x20Method\x20names,\x20for\x20example\x20\"SayHello\".\n\n\x0c\n\
+    \x05\x04\x01\x02\x01\x04\x12\x03\x0e\x02\n\n\x0c\n\x05\x04\x01\x02\x01\
+    \x05\x12\x03\x0e\x0b\x11\n\x0c\n\x05\x04\x01\x02\x01\x01\x12\x03\x0e\x12\
+    \x19\n\x0c\n\x05\x04\x01\x02\x01\x03\x12\x03\x0e\x1c\x1d\nR\n\x04\x04\
+    \x01\x02\x02\x12\x03\x11\x02\x12\x1aE\x20Name\x20of\x20the\x20file\x20de\
+    fining\x20the\x20service,\x20for\x20example\x20\"service.proto\".\n\n\
+    \x0c\n\x05\x04\x01\x02\x02\x05\x12\x03\x11\x02\x08\n\x0c\n\x05\x04\x01\
+    \x02\x02\x01\x12\x03\x11\t\r\n\x0c\n\x05\x04\x01\x02\x02\x03\x12\x03\x11\
+    \x10\x11\n\n\n\x02\x04\x02\x12\x04\x14\0\x16\x01\n\n\n\x03\x04\x02\x01\
+    \x12\x03\x14\x08\x1c\n\x0b\n\x04\x04\x02\x02\0\x12\x03\x15\x02$\n\x0c\n\
+    \x05\x04\x02\x02\0\x04\x12\x03\x15\x02\n\n\x0c\n\x05\x04\x02\x02\0\x06\
+    \x12\x03\x15\x0b\x16\n\x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x15\x17\x1f\n\
+    \x0c\n\x05\x04\x02\x02\0\x03\x12\x03\x15\"#\n\n\n\x02\x04\x03\x12\x04\
+    \x18\0\x1d\x01\n\n\n\x03\x04\x03\x01\x12\x03\x18\x08#\n\x9b\x01\n\x04\
+    \x04\x03\x02\0\x12\x03\x1c\x02\x14\x1a\x8d\x01\x20Fully\x20qualified\x20\
+    service,\x20method\x20or\x20message\x20name,\x20for\x20example\n\x20\"se\
+    rvice.MyService\",\x20\"service.MyService.SayHello\"\x20or\n\x20\"servic\
+    e.HelloRequest\".\n\n\x0c\n\x05\x04\x03\x02\0\x05\x12\x03\x1c\x02\x08\n\
+    \x0c\n\x05\x04\x03\x02\0\x01\x12\x03\x1c\t\x0f\n\x0c\n\x05\x04\x03\x02\0\
+    \x03\x12\x03\x1c\x12\x13\n\n\n\x02\x04\x04\x12\x04\x1f\0#\x01\n\n\n\x03\
+    \x04\x04\x01\x12\x03\x1f\x08\x1e\n_\n\x04\x04\x04\x02\0\x12\x03\"\x02\"\
+    \x1aR\x20Serialized\x20google.protobuf.FileDescriptorProto\x20of\x20the\
+    \x20file\x20defining\n\x20the\x20symbol.\n\n\x0c\n\x05\x04\x04\x02\0\x05\
+    \x12\x03\"\x02\x07\n\x0c\n\x05\x04\x04\x02\0\x01\x12\x03\"\x08\x1d\n\x0c\
+    \n\x05\x04\x04\x02\0\x03\x12\x03\"\x20!\n\n\n\x02\x06\0\x12\x04%\0,\x01\
+    \n\n\n\x03\x06\0\x01\x12\x03%\x08\x18\n<\n\x04\x06\0\x02\0\x12\x03'\x02J\
+    \x1a/\x20Lists\x20the\x20services\x20the\x20server\x20has\x20registered.\
+    \n\n\x0c\n\x05\x06\0\x02\0\x01\x12\x03'\x06\x12\n\x0c\n\x05\x06\0\x02\0\
+    \x02\x12\x03'\x14'\n\x0c\n\x05\x06\0\x02\0\x03\x12\x03'2F\nj\n\x04\x06\0\
+    \x02\x01\x12\x03+\x02\\\x1a]\x20Returns\x20the\x20file\x20defining\x20a\
+    \x20symbol,\x20or\x20a\x20NOT_FOUND\x20error\x20if\x20no\n\x20registered\
+    \x20service\x20uses\x20it.\n\n\x0c\n\x05\x06\0\x02\x01\x01\x12\x03+\x06\
+    \x1a\n\x0c\n\x05\x06\0\x02\x01\x02\x12\x03+\x1c7\n\x0c\n\x05\x06\0\x02\
+    \x01\x03\x12\x03+BXb\x06proto3\
+";
+
+static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy::INIT;
+
+fn parse_descriptor_proto() -> ::protobuf::descriptor::FileDescriptorProto {
+    ::protobuf::parse_from_bytes(file_descriptor_proto_data).unwrap()
+}
+
+pub fn file_descriptor_proto() -> &'static ::protobuf::descriptor::FileDescriptorProto {
+    unsafe {
+        file_descriptor_proto_lazy.get(|| {
+            parse_descriptor_proto()
+        })
+    }
+}
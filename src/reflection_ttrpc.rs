@@ -0,0 +1,90 @@
+// This file is generated by ttrpc-compiler 0.2.0. Do not edit
+// @generated
+
+// https://github.com/Manishearth/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clipto_camel_casepy)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+use protobuf::{CodedInputStream, CodedOutputStream, Message};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone)]
+pub struct ServerReflectionClient {
+    client: ::ttrpc::Client,
+}
+
+impl ServerReflectionClient {
+    pub fn new(client: ::ttrpc::Client) -> Self {
+        ServerReflectionClient {
+            client: client,
+        }
+    }
+
+    pub fn list_services(&self, req: &super::reflection::ListServicesRequest, timeout_nano: i64) -> ::ttrpc::Result<super::reflection::ListServicesResponse> {
+        let mut cres = super::reflection::ListServicesResponse::new();
+        ::ttrpc::client_request!(self, req, timeout_nano, "ttrpc.reflection.v1.ServerReflection", "ListServices", cres);
+        Ok(cres)
+    }
+
+    pub fn file_containing_symbol(&self, req: &super::reflection::FileContainingSymbolRequest, timeout_nano: i64) -> ::ttrpc::Result<super::reflection::FileDescriptorResponse> {
+        let mut cres = super::reflection::FileDescriptorResponse::new();
+        ::ttrpc::client_request!(self, req, timeout_nano, "ttrpc.reflection.v1.ServerReflection", "FileContainingSymbol", cres);
+        Ok(cres)
+    }
+}
+
+struct ListServicesMethod {
+    service: Arc<std::boxed::Box<dyn ServerReflection + Send + Sync>>,
+}
+
+impl ::ttrpc::MethodHandler for ListServicesMethod {
+    fn handler(&self, ctx: ::ttrpc::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<()> {
+        ::ttrpc::request_handler!(self, ctx, req, reflection, ListServicesRequest, list_services);
+        Ok(())
+    }
+}
+
+struct FileContainingSymbolMethod {
+    service: Arc<std::boxed::Box<dyn ServerReflection + Send + Sync>>,
+}
+
+impl ::ttrpc::MethodHandler for FileContainingSymbolMethod {
+    fn handler(&self, ctx: ::ttrpc::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<()> {
+        ::ttrpc::request_handler!(self, ctx, req, reflection, FileContainingSymbolRequest, file_containing_symbol);
+        Ok(())
+    }
+}
+
+pub trait ServerReflection {
+    fn list_services(&self, _ctx: &::ttrpc::TtrpcContext, _req: super::reflection::ListServicesRequest) -> ::ttrpc::Result<super::reflection::ListServicesResponse> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/ttrpc.reflection.v1.ServerReflection/ListServices is not supported".to_string())))
+    }
+    fn file_containing_symbol(&self, _ctx: &::ttrpc::TtrpcContext, _req: super::reflection::FileContainingSymbolRequest) -> ::ttrpc::Result<super::reflection::FileDescriptorResponse> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/ttrpc.reflection.v1.ServerReflection/FileContainingSymbol is not supported".to_string())))
+    }
+}
+
+pub fn create_server_reflection(service: Arc<std::boxed::Box<dyn ServerReflection + Send + Sync>>) -> HashMap <String, Box<dyn ::ttrpc::MethodHandler + Send + Sync>> {
+    let mut methods = HashMap::new();
+
+    methods.insert("/ttrpc.reflection.v1.ServerReflection/ListServices".to_string(),
+                    std::boxed::Box::new(ListServicesMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::MethodHandler + Send + Sync>);
+
+    methods.insert("/ttrpc.reflection.v1.ServerReflection/FileContainingSymbol".to_string(),
+                    std::boxed::Box::new(FileContainingSymbolMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::MethodHandler + Send + Sync>);
+
+    methods
+}
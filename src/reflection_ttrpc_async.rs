@@ -0,0 +1,90 @@
+// This file is generated by ttrpc-compiler 0.4.0. Do not edit
+// @generated
+
+// https://github.com/Manishearth/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clipto_camel_casepy)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+use protobuf::{CodedInputStream, CodedOutputStream, Message};
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+
+#[derive(Clone)]
+pub struct ServerReflectionClient {
+    client: ::ttrpc::r#async::Client,
+}
+
+impl ServerReflectionClient {
+    pub fn new(client: ::ttrpc::r#async::Client) -> Self {
+        ServerReflectionClient {
+            client: client,
+        }
+    }
+
+    pub async fn list_services(&self, req: &super::reflection::ListServicesRequest, timeout_nano: i64) -> ::ttrpc::Result<super::reflection::ListServicesResponse> {
+        let mut cres = super::reflection::ListServicesResponse::new();
+        ::ttrpc::async_client_request!(self, req, timeout_nano, "ttrpc.reflection.v1.ServerReflection", "ListServices", cres);
+    }
+
+    pub async fn file_containing_symbol(&self, req: &super::reflection::FileContainingSymbolRequest, timeout_nano: i64) -> ::ttrpc::Result<super::reflection::FileDescriptorResponse> {
+        let mut cres = super::reflection::FileDescriptorResponse::new();
+        ::ttrpc::async_client_request!(self, req, timeout_nano, "ttrpc.reflection.v1.ServerReflection", "FileContainingSymbol", cres);
+    }
+}
+
+struct ListServicesMethod {
+    service: Arc<std::boxed::Box<dyn ServerReflection + Send + Sync>>,
+}
+
+#[async_trait]
+impl ::ttrpc::r#async::MethodHandler for ListServicesMethod {
+    async fn handler(&self, ctx: ::ttrpc::r#async::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<(u32, Vec<u8>)> {
+        ::ttrpc::async_request_handler!(self, ctx, req, reflection, ListServicesRequest, list_services);
+    }
+}
+
+struct FileContainingSymbolMethod {
+    service: Arc<std::boxed::Box<dyn ServerReflection + Send + Sync>>,
+}
+
+#[async_trait]
+impl ::ttrpc::r#async::MethodHandler for FileContainingSymbolMethod {
+    async fn handler(&self, ctx: ::ttrpc::r#async::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<(u32, Vec<u8>)> {
+        ::ttrpc::async_request_handler!(self, ctx, req, reflection, FileContainingSymbolRequest, file_containing_symbol);
+    }
+}
+
+#[async_trait]
+pub trait ServerReflection: Sync {
+    async fn list_services(&self, _ctx: &::ttrpc::r#async::TtrpcContext, _req: super::reflection::ListServicesRequest) -> ::ttrpc::Result<super::reflection::ListServicesResponse> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/ttrpc.reflection.v1.ServerReflection/ListServices is not supported".to_string())))
+    }
+    async fn file_containing_symbol(&self, _ctx: &::ttrpc::r#async::TtrpcContext, _req: super::reflection::FileContainingSymbolRequest) -> ::ttrpc::Result<super::reflection::FileDescriptorResponse> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/ttrpc.reflection.v1.ServerReflection/FileContainingSymbol is not supported".to_string())))
+    }
+}
+
+pub fn create_server_reflection(service: Arc<std::boxed::Box<dyn ServerReflection + Send + Sync>>) -> HashMap <String, Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>> {
+    let mut methods = HashMap::new();
+
+    methods.insert("/ttrpc.reflection.v1.ServerReflection/ListServices".to_string(),
+                    std::boxed::Box::new(ListServicesMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>);
+
+    methods.insert("/ttrpc.reflection.v1.ServerReflection/FileContainingSymbol".to_string(),
+                    std::boxed::Box::new(FileContainingSymbolMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>);
+
+    methods
+}
@@ -1,24 +1,642 @@
 // Description: Server side of ttRPC comms
 
+#[cfg(feature = "async")]
+use crate::async_server::AsyncServers;
+use crate::auth::AccessPolicy;
+use crate::error::{Error, Result};
+use crate::health::HealthCheckResponse_ServingStatus as ServingStatus;
+use crate::health_ttrpc::{create_health, Health};
+use crate::healthcheck::{HealthService, HEALTH_SERVICE_NAME, MY_SERVICE_NAME};
+use crate::interceptor::{Interceptor, InterceptorChain};
+use crate::reflect::{ReflectionService, REFLECTION_SERVICE_NAME};
+use crate::reflection_ttrpc::{create_server_reflection, ServerReflection};
 use crate::service::{HelloReply, HelloRequest, ShutdownReply, ShutdownRequest};
 use crate::service_ttrpc::{create_my_service, MyService};
-use crate::types::HelloService;
+use crate::signals;
+use crate::socket::{abstract_sock_addr, abstract_socket_name, listen_fd, AbstractNaming};
+use crate::systemd::{self, NOTIFY_READY, NOTIFY_STOPPING};
+use crate::types::{Config, HelloService, RequestContext, ShutdownParams, ShutdownTrigger};
+use crate::uri::ServerUri;
+use crate::{health, reflection, service};
+use nix::sys::socket::{getsockname, AddressFamily, SockAddr};
 use nix::unistd::close;
 use ttrpc::error::Error as TError;
 use ttrpc::error::Result as TResult;
 use ttrpc::server::Server;
 use ttrpc::ttrpc::{Code, Status};
-use ttrpc::TtrpcContext;
+use ttrpc::{MethodHandler, TtrpcContext};
 
-use slog::{error, info};
-use std::sync::mpsc::Receiver;
-use std::sync::Arc;
+use slog::{error, info, o, warn, Logger};
+use slog_scope::GlobalLoggerGuard;
+use std::collections::HashMap;
+use std::fs;
+use std::net::{SocketAddr, TcpListener, ToSocketAddrs};
+use std::os::unix::io::{IntoRawFd, RawFd};
+use std::os::unix::net::UnixListener;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
 
-pub type Result<T> = std::result::Result<T, String>;
+/// How long in-flight requests are given to finish by default when the
+/// server is asked to shut down.
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(5);
 
-impl<'a> MyService for HelloService {
-    fn say_hello(&self, _ctx: &TtrpcContext, req: HelloRequest) -> TResult<HelloReply> {
-        let msg = format!("Hello '{}'", req.get_name());
+// How often to check whether in-flight requests have finished.
+const DRAIN_POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+// How long to wait for Shutdown requests to hand over their replies before
+// stopping the server. They do not block, so only a stuck server takes
+// this long.
+const SHUTDOWN_REPLY_TIMEOUT: Duration = Duration::from_secs(1);
+
+// Create a listening abstract Unix socket called "name".
+fn server_create_abstract_fd(name: &str, naming: AbstractNaming) -> Result<RawFd> {
+    let sock_addr = abstract_sock_addr(name, naming)?;
+
+    let fd = listen_fd(AddressFamily::Unix, &sock_addr)?;
+
+    // Log the full name, including any trailing NUL, since that is what a
+    // client must use to connect.
+    info!(sl!(), "bound abstract socket";
+        "abstract-name" => format!("@{}", abstract_socket_name(name, naming).escape_default()),
+        "naming" => naming.to_string());
+
+    Ok(fd)
+}
+
+// Create a listening abstract Unix socket called "name" for each naming
+// convention of "naming".
+pub(crate) fn server_create_abstract_fds(name: &str, naming: AbstractNaming) -> Result<Vec<RawFd>> {
+    let mut fds = Vec::new();
+
+    for convention in naming.conventions() {
+        match server_create_abstract_fd(name, *convention) {
+            Ok(fd) => fds.push(fd),
+            Err(e) => {
+                fds.iter().for_each(|fd| {
+                    let _ = close(*fd);
+                });
+
+                return Err(e);
+            }
+        }
+    }
+
+    Ok(fds)
+}
+
+// Create a listening vsock socket, returning it along with the port it is
+// bound to (which is chosen by the kernel if "port" is
+// libc::VMADDR_PORT_ANY). Unlike the "ttrpc" crate, which always binds to
+// libc::VMADDR_CID_ANY, this honours the requested CID.
+fn server_create_vsock_fd(cid: u32, port: u32) -> Result<(RawFd, u32)> {
+    let sock_addr = SockAddr::new_vsock(cid, port);
+
+    let fd = match listen_fd(AddressFamily::Vsock, &sock_addr) {
+        Ok(fd) => fd,
+        Err(e) => return Err(Error::Vsock(e.to_string())),
+    };
+
+    let bound_port = match getsockname(fd) {
+        Ok(SockAddr::Vsock(addr)) => addr.port(),
+        Ok(addr) => {
+            let _ = close(fd);
+            return Err(Error::Vsock(format!(
+                "unexpected address for vsock socket: {}",
+                addr
+            )));
+        }
+        Err(e) => {
+            let _ = close(fd);
+            return Err(Error::Vsock(format!(
+                "failed to query vsock socket address: {:?}",
+                e
+            )));
+        }
+    };
+
+    info!(sl!(), "bound vsock socket";
+        "cid" => cid,
+        "requested-port" => ServerUri::Vsock { cid, port }.to_string(),
+        "port" => bound_port);
+
+    Ok((fd, bound_port))
+}
+
+// Create a listening TCP socket, returning it along with the port it is
+// bound to (which is chosen by the kernel if "port" is 0). Only loopback
+// addresses are allowed unless "allow_remote" is set since ttRPC has no
+// authentication or encryption.
+fn server_create_tcp_fd(host: &str, port: u16, allow_remote: bool) -> Result<(RawFd, u16)> {
+    let addrs: Vec<SocketAddr> = match (host, port).to_socket_addrs() {
+        Ok(a) => a.collect(),
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "failed to resolve TCP host {:?}: {:?}",
+                host, e
+            )))
+        }
+    };
+
+    if !allow_remote {
+        if let Some(addr) = addrs.iter().find(|a| !a.ip().is_loopback()) {
+            return Err(Error::Usage(format!(
+                "refusing to listen on non-loopback TCP address {} (allow remote TCP connections to override)",
+                addr
+            )));
+        }
+    }
+
+    let listener = match TcpListener::bind(&addrs[..]) {
+        Ok(l) => l,
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "failed to bind TCP socket to {:?}: {:?}",
+                addrs, e
+            )))
+        }
+    };
+
+    let local_addr = match listener.local_addr() {
+        Ok(a) => a,
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "failed to query TCP socket address: {:?}",
+                e
+            )))
+        }
+    };
+
+    info!(sl!(), "bound TCP socket";
+        "address" => local_addr.to_string(),
+        "port" => local_addr.port());
+
+    Ok((listener.into_raw_fd(), local_addr.port()))
+}
+
+// Create a ttRPC server for an existing listening socket. The socket is
+// left open on error.
+fn server_from_fd(fd: RawFd) -> Result<Server> {
+    Ok(Server::new().add_listener(fd)?)
+}
+
+// Create the listening sockets for "cfg.server_uri". There is more than one
+// socket only for an abstract Unix socket using "auto" naming. Also returns
+// the address actually bound, which differs from "cfg.server_uri" if the
+// kernel chose the port.
+pub fn server_listen_fds(cfg: &Config) -> Result<(Vec<RawFd>, ServerUri)> {
+    let server_uri = &cfg.server_uri;
+
+    let mut bound_uri = server_uri.clone();
+
+    let fds = match server_uri {
+        ServerUri::UnixAbstract(name) => server_create_abstract_fds(name, cfg.abstract_naming)?,
+        ServerUri::UnixPath(path) => {
+            let listener = match UnixListener::bind(path) {
+                Ok(l) => l,
+                Err(e) => {
+                    return Err(Error::Socket(format!(
+                        "failed to bind server to {:?}: {:?}",
+                        path, e
+                    )))
+                }
+            };
+
+            vec![listener.into_raw_fd()]
+        }
+        ServerUri::Vsock { cid, port } => {
+            let (fd, bound_port) = server_create_vsock_fd(*cid, *port)?;
+
+            bound_uri = ServerUri::Vsock {
+                cid: *cid,
+                port: bound_port,
+            };
+
+            vec![fd]
+        }
+        ServerUri::Tcp { host, port } => {
+            let (fd, bound_port) = server_create_tcp_fd(host, *port, cfg.allow_remote_tcp)?;
+
+            bound_uri = ServerUri::Tcp {
+                host: host.clone(),
+                port: bound_port,
+            };
+
+            vec![fd]
+        }
+        ServerUri::HybridVsock { .. } => {
+            return Err(Error::Usage(format!(
+                "cannot listen on a hybrid vsock URI (the VMM owns the socket): {}",
+                server_uri
+            )))
+        }
+    };
+
+    Ok((fds, bound_uri))
+}
+
+// A listening socket and the address it is bound to.
+pub(crate) type Listener = (RawFd, ServerUri);
+
+// The listening sockets of a server being built. Unless the build succeeds
+// and calls disarm(), they are closed when dropped, and any Unix socket
+// files server_bind bound them to are removed.
+#[derive(Default)]
+pub(crate) struct BoundListeners {
+    listeners: Vec<Listener>,
+    paths: Vec<PathBuf>,
+}
+
+impl BoundListeners {
+    // Take ownership of sockets passed by the service manager, whose socket
+    // files are its own to remove.
+    fn adopt(listeners: Vec<Listener>) -> Self {
+        BoundListeners {
+            listeners,
+            paths: Vec::new(),
+        }
+    }
+
+    pub fn listeners(&self) -> &[Listener] {
+        &self.listeners
+    }
+
+    fn add(&mut self, fds: Vec<RawFd>, bound_uri: &ServerUri) {
+        if let ServerUri::UnixPath(path) = bound_uri {
+            self.paths.push(PathBuf::from(path));
+        }
+
+        for fd in fds {
+            self.listeners.push((fd, bound_uri.clone()));
+        }
+    }
+
+    // Hand the sockets over to the servers created for them.
+    fn disarm(mut self) {
+        self.listeners.clear();
+        self.paths.clear();
+    }
+}
+
+impl Drop for BoundListeners {
+    fn drop(&mut self) {
+        for (fd, _) in &self.listeners {
+            let _ = close(*fd);
+        }
+
+        for path in &self.paths {
+            if let Err(e) = fs::remove_file(path) {
+                warn!(sl!(), "failed to remove socket";
+                    "path" => path.display().to_string(),
+                    "error" => e.to_string());
+            }
+        }
+    }
+}
+
+// Create the listening sockets for each of "server_uris", using "cfg" for
+// the remaining settings. Returns the sockets, each along with the address
+// it is bound to, and the addresses actually bound (one per server URI). If
+// any cannot be created, those already created are closed and removed.
+fn server_bind(
+    cfg: &Config,
+    server_uris: &[ServerUri],
+) -> Result<(BoundListeners, Vec<ServerUri>)> {
+    let mut bound = BoundListeners::default();
+    let mut bound_uris = Vec::new();
+
+    for server_uri in server_uris {
+        let uri_cfg = Config {
+            server_uri: server_uri.clone(),
+            ..cfg.clone()
+        };
+
+        let (fds, bound_uri) = server_listen_fds(&uri_cfg)?;
+
+        bound.add(fds, &bound_uri);
+
+        bound_uris.push(bound_uri);
+    }
+
+    Ok((bound, bound_uris))
+}
+
+// Create a ttRPC server for each listening socket, serving "service",
+// "health" and "reflection" with "interceptors" around every method. There
+// is one server per listening socket as a ttRPC server only accepts
+// connections on a single socket. The sockets remain the caller's to close
+// on error.
+fn sync_servers(
+    listeners: &[Listener],
+    service: &HelloService,
+    health: &HealthService,
+    reflection: &ReflectionService,
+    interceptors: &InterceptorChain,
+) -> Result<Vec<Server>> {
+    let s = Box::new(service.clone()) as Box<dyn MyService + Send + Sync>;
+    let s = Arc::new(s);
+
+    let h = Box::new(health.clone()) as Box<dyn Health + Send + Sync>;
+    let h = Arc::new(h);
+
+    let r = Box::new(reflection.clone()) as Box<dyn ServerReflection + Send + Sync>;
+    let r = Arc::new(r);
+
+    let mut servers = Vec::new();
+
+    for (fd, _) in listeners {
+        let server = server_from_fd(*fd)?
+            .register_service(track_methods(
+                service,
+                interceptors.wrap(create_my_service(s.clone())),
+            ))
+            .register_service(interceptors.wrap(create_health(h.clone())))
+            .register_service(interceptors.wrap(create_server_reflection(r.clone())));
+
+        servers.push(server);
+    }
+
+    Ok(servers)
+}
+
+#[cfg(feature = "async")]
+fn async_servers(
+    listeners: &[Listener],
+    service: &HelloService,
+    health: &HealthService,
+    reflection: &ReflectionService,
+    interceptors: &InterceptorChain,
+) -> Result<Servers> {
+    let servers = AsyncServers::new(listeners, service, health, reflection, interceptors)?;
+
+    Ok(Servers::Async(servers))
+}
+
+#[cfg(not(feature = "async"))]
+fn async_servers(
+    _listeners: &[Listener],
+    _service: &HelloService,
+    _health: &HealthService,
+    _reflection: &ReflectionService,
+    _interceptors: &InterceptorChain,
+) -> Result<Servers> {
+    Err(Error::Usage(
+        "async server requires building with the \"async\" feature".to_string(),
+    ))
+}
+
+// The ttRPC servers of a HelloServer, which all serve the same handlers.
+enum Servers {
+    Sync(Vec<Server>),
+
+    #[cfg(feature = "async")]
+    Async(AsyncServers),
+}
+
+impl Servers {
+    fn start(&mut self) -> Result<()> {
+        match self {
+            Servers::Sync(servers) => {
+                for server in servers.iter_mut() {
+                    server.start()?;
+                }
+
+                Ok(())
+            }
+
+            #[cfg(feature = "async")]
+            Servers::Async(servers) => servers.start(),
+        }
+    }
+
+    fn shutdown(self) {
+        match self {
+            Servers::Sync(servers) => {
+                for server in servers {
+                    server.shutdown();
+                }
+            }
+
+            #[cfg(feature = "async")]
+            Servers::Async(servers) => servers.shutdown(),
+        }
+    }
+}
+
+// Write the port of the first vsock or TCP URI the server is listening on
+// to "path". The file is renamed into place so a reader never sees a
+// partial port number.
+fn write_port_file(path: &Path, server_uris: &[ServerUri]) -> Result<()> {
+    let port = server_uris.iter().find_map(|uri| match uri {
+        ServerUri::Vsock { port, .. } => Some(*port),
+        ServerUri::Tcp { port, .. } => Some(u32::from(*port)),
+        _ => None,
+    });
+
+    let port = match port {
+        Some(p) => p,
+        None => {
+            return Err(Error::Usage(
+                "port file requires a vsock or TCP server URI".to_string(),
+            ))
+        }
+    };
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    let result = fs::write(&tmp, format!("{}\n", port)).and_then(|_| fs::rename(&tmp, path));
+
+    if let Err(e) = result {
+        return Err(Error::Io(format!(
+            "failed to write port file {:?}: {}",
+            path, e
+        )));
+    }
+
+    info!(sl!(), "wrote port file";
+        "path" => path.display().to_string(),
+        "port" => port);
+
+    Ok(())
+}
+
+// Names of the MyService methods, as used in access policies.
+const SAY_HELLO_METHOD: &str = "SayHello";
+const SHUTDOWN_METHOD: &str = "Shutdown";
+
+const SERVICE_METHODS: &[&str] = &[SAY_HELLO_METHOD, SHUTDOWN_METHOD];
+
+/// Metadata key choosing the language of greetings (for example "fr").
+pub const GREETING_LANGUAGE_KEY: &str = "greeting-language";
+
+// The greeting in each supported language. The first is used if the
+// request asks for none, or an unknown one.
+const GREETINGS: &[(&str, &str)] = &[
+    ("en", "Hello"),
+    ("de", "Hallo"),
+    ("es", "Hola"),
+    ("fr", "Bonjour"),
+];
+
+fn greeting(ctx: &RequestContext) -> &'static str {
+    let language = ctx
+        .metadata_value(GREETING_LANGUAGE_KEY)
+        .unwrap_or_default();
+
+    GREETINGS
+        .iter()
+        .find(|(l, _)| l.eq_ignore_ascii_case(language))
+        .unwrap_or(&GREETINGS[0])
+        .1
+}
+
+// The handler's view of a request, from the sync server's context.
+pub(crate) fn request_context(ctx: &TtrpcContext) -> RequestContext {
+    RequestContext::new(ctx.fd, ctx.metadata.clone())
+}
+
+pub(crate) fn rpc_error(code: Code, msg: String) -> TError {
+    let mut status = Status::new();
+
+    status.set_code(code);
+    status.set_message(msg);
+
+    TError::RpcStatus(status)
+}
+
+// Counts a request as in flight for as long as it exists.
+pub(crate) struct InFlightGuard(Arc<AtomicUsize>);
+
+impl InFlightGuard {
+    pub(crate) fn new(in_flight: &Arc<AtomicUsize>) -> Self {
+        in_flight.fetch_add(1, Ordering::SeqCst);
+
+        InFlightGuard(in_flight.clone())
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// A sync method handler whose requests count as in flight until it returns,
+// by which time it has handed its reply to the connection (see
+// ttrpc::response_to_channel).
+struct TrackedMethod {
+    handler: Box<dyn MethodHandler + Send + Sync>,
+    in_flight: Arc<AtomicUsize>,
+}
+
+impl MethodHandler for TrackedMethod {
+    fn handler(&self, ctx: TtrpcContext, req: ttrpc::Request) -> TResult<()> {
+        let _guard = InFlightGuard::new(&self.in_flight);
+
+        self.handler.handler(ctx, req)
+    }
+}
+
+// Wrap each handler in "methods", the sync MyService methods, so its
+// requests are counted by "service".
+fn track_methods(
+    service: &HelloService,
+    methods: HashMap<String, Box<dyn MethodHandler + Send + Sync>>,
+) -> HashMap<String, Box<dyn MethodHandler + Send + Sync>> {
+    methods
+        .into_iter()
+        .map(|(method, handler)| {
+            let tracked = TrackedMethod {
+                handler,
+                in_flight: service.in_flight_counter(&method).clone(),
+            };
+
+            (
+                method,
+                Box::new(tracked) as Box<dyn MethodHandler + Send + Sync>,
+            )
+        })
+        .collect()
+}
+
+impl HelloService {
+    fn new(cfg: Config) -> Self {
+        HelloService {
+            cfg: Arc::new(Mutex::new(cfg)),
+            in_flight: Arc::new(AtomicUsize::new(0)),
+            shutdowns_in_flight: Arc::new(AtomicUsize::new(0)),
+            shutting_down: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    // The counter for requests to "method" (as "/package.Service/Method").
+    pub(crate) fn in_flight_counter(&self, method: &str) -> &Arc<AtomicUsize> {
+        match method == format!("/{}/{}", MY_SERVICE_NAME, SHUTDOWN_METHOD) {
+            true => &self.shutdowns_in_flight,
+            false => &self.in_flight,
+        }
+    }
+
+    // Check "method" may be called, which it may not once the server is
+    // shutting down.
+    fn admit_request(&self, ctx: &RequestContext, method: &str) -> TResult<()> {
+        self.authorize(ctx, method)?;
+
+        if self.shutting_down.load(Ordering::SeqCst) {
+            return Err(rpc_error(
+                Code::UNAVAILABLE,
+                "server is shutting down".to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    // Log the identity of the peer calling "method" and check the access
+    // policy allows it to.
+    fn authorize(&self, ctx: &RequestContext, method: &str) -> TResult<()> {
+        let peer = ctx.log_request(MY_SERVICE_NAME, method);
+
+        let peer_desc = match &peer {
+            Some(p) => p.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        let allowed = self
+            .cfg
+            .lock()
+            .unwrap()
+            .access_policy
+            .is_allowed(method, peer.as_ref());
+
+        if allowed {
+            return Ok(());
+        }
+
+        warn!(sl!(), "permission denied"; "method" => method, "peer" => peer_desc.clone());
+
+        Err(rpc_error(
+            Code::PERMISSION_DENIED,
+            format!("peer ({}) may not call {}", peer_desc, method),
+        ))
+    }
+}
+
+// The handlers, shared by the sync and async services, which call them
+// within the request's logging scope (see RequestContext::scope).
+impl HelloService {
+    pub(crate) fn handle_say_hello(
+        &self,
+        ctx: &RequestContext,
+        req: HelloRequest,
+    ) -> TResult<HelloReply> {
+        self.admit_request(ctx, SAY_HELLO_METHOD)?;
+
+        let msg = format!("{} '{}'", greeting(ctx), req.get_name());
 
         info!(sl!(), "server responding";
             "client-request" => req.get_name(),
@@ -28,96 +646,622 @@ impl<'a> MyService for HelloService {
 
         resp.set_message(msg);
 
-        Ok(HelloReply::new())
+        Ok(resp)
     }
 
-    fn shutdown(&self, ctx: &TtrpcContext, req: ShutdownRequest) -> TResult<ShutdownReply> {
+    pub(crate) fn handle_shutdown(
+        &self,
+        ctx: &RequestContext,
+        req: ShutdownRequest,
+    ) -> TResult<ShutdownReply> {
+        // Always accepted, so a forced shutdown can override a graceful
+        // one in progress.
+        self.authorize(ctx, SHUTDOWN_METHOD)?;
+
         info!(sl!(), "server responding";
             "command" => "shutdown",
             "client-request" => format!("{:?}", req));
 
-        let cfg_ref = self.cfg.clone();
-        let cfg = cfg_ref.lock().unwrap();
+        let (tx, default_grace_period) = {
+            let cfg = self.cfg.lock().unwrap();
+
+            (cfg.tx.clone(), cfg.shutdown_grace_period)
+        };
+
+        let grace_period = match req.get_grace_period_ms() {
+            0 => default_grace_period,
+            ms => Duration::from_millis(ms),
+        };
+
+        let params = ShutdownParams {
+            trigger: ShutdownTrigger::Request,
+            reason: req.get_reason().to_string(),
+            grace_period: Some(grace_period),
+            force: req.get_force(),
+        };
 
-        if cfg.tx.is_none() {
-            let err_msg = "No sender channel".to_string();
+        let tx = match tx {
+            Some(tx) => tx,
+            None => {
+                error!(sl!(), "no shutdown channel");
 
-            error!(sl!(), "{}", err_msg);
+                return Err(rpc_error(
+                    Code::FAILED_PRECONDITION,
+                    "server cannot be shut down remotely".to_string(),
+                ));
+            }
+        };
 
-            let mut status = Status::new();
+        let already_shutting_down = self.shutting_down.swap(true, Ordering::SeqCst);
 
-            // FIXME:
-            status.set_code(Code::NOT_FOUND);
-            status.set_message("foo".to_string());
+        if already_shutting_down && !params.force {
+            return Err(rpc_error(
+                Code::FAILED_PRECONDITION,
+                "shutdown already in progress (set force to stop immediately)".to_string(),
+            ));
+        }
+
+        info!(sl!(), "requesting shutdown";
+            "reason" => params.reason.clone(),
+            "grace-period" => format!("{:?}", grace_period),
+            "force" => params.force);
+
+        if let Err(e) = tx.send(params.clone()) {
+            error!(sl!(), "failed to request shutdown"; "error" => format!("{:?}", e));
 
-            return Err(TError::RpcStatus(status));
+            return Err(rpc_error(
+                Code::UNAVAILABLE,
+                "server is no longer waiting for a shutdown request".to_string(),
+            ));
         }
 
-        let tx = cfg.tx.as_ref().unwrap();
+        let pending = self.in_flight.load(Ordering::SeqCst);
+
+        let reason = match params.reason.is_empty() {
+            true => "".to_string(),
+            false => format!(" (reason: {})", params.reason),
+        };
+
+        let msg = match params.force {
+            true => format!(
+                "forced shutdown{}: abandoning {} in-flight request(s)",
+                reason, pending
+            ),
+            false => format!(
+                "shutting down{}: waiting up to {:?} for {} in-flight request(s)",
+                reason, grace_period, pending
+            ),
+        };
+
+        info!(sl!(), "requested shutdown"; "result" => msg.clone());
+
+        let mut resp = ShutdownReply::new();
+
+        resp.set_message(msg);
+
+        Ok(resp)
+    }
+}
+
+impl<'a> MyService for HelloService {
+    fn say_hello(&self, ctx: &TtrpcContext, req: HelloRequest) -> TResult<HelloReply> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_say_hello(&ctx, req))
+    }
+
+    fn shutdown(&self, ctx: &TtrpcContext, req: ShutdownRequest) -> TResult<ShutdownReply> {
+        let ctx = request_context(ctx);
+
+        ctx.scope(|| self.handle_shutdown(&ctx, req))
+    }
+}
+
+/// Builder for a [`HelloServer`] serving `MyService`.
+#[derive(Debug, Default)]
+pub struct ServerBuilder {
+    server_uris: Vec<String>,
+    force_abstract_socket: bool,
+    abstract_naming: AbstractNaming,
+    port_file: Option<PathBuf>,
+    allow_remote_tcp: bool,
+    access_policy: AccessPolicy,
+    shutdown_grace_period: Option<Duration>,
+    handle_signals: bool,
+    use_async: bool,
+    interceptors: InterceptorChain,
+    logger: Option<Logger>,
+}
+
+impl ServerBuilder {
+    pub fn new() -> Self {
+        ServerBuilder::default()
+    }
+
+    /// URI to listen on (`unix:///some/where`, `vsock://cid:port` or
+    /// `tcp://host:port`). Use `vsock://cid:any` or `tcp://host:0` to have
+    /// the kernel choose a free port. Call more than once to serve on
+    /// several URIs at the same time. Ignored (and optional) if the process
+    /// is passed listening sockets by systemd socket activation.
+    pub fn server_uri(mut self, server_uri: &str) -> Self {
+        self.server_uris.push(server_uri.to_string());
+        self
+    }
+
+    /// Treat Unix socket paths as abstract, even without an `@` prefix.
+    pub fn abstract_socket(mut self, force_abstract_socket: bool) -> Self {
+        self.force_abstract_socket = force_abstract_socket;
+        self
+    }
+
+    /// How abstract socket names are encoded (default: the "ttrpc" crate's
+    /// trailing-NUL convention). `Auto` listens on both names.
+    pub fn abstract_naming(mut self, abstract_naming: AbstractNaming) -> Self {
+        self.abstract_naming = abstract_naming;
+        self
+    }
+
+    /// Allow listening on non-loopback TCP addresses. By default, a
+    /// `tcp://` server URI must resolve to loopback addresses only.
+    pub fn allow_remote_tcp(mut self, allow_remote_tcp: bool) -> Self {
+        self.allow_remote_tcp = allow_remote_tcp;
+        self
+    }
+
+    /// Which peers may call each method (for example, only uid 0 may call
+    /// `Shutdown`). By default, anyone able to connect may call anything.
+    pub fn access_policy(mut self, access_policy: AccessPolicy) -> Self {
+        self.access_policy = access_policy;
+        self
+    }
+
+    /// How long in-flight requests may take to finish once a shutdown is
+    /// requested, unless the request gives its own grace period (default:
+    /// [`DEFAULT_SHUTDOWN_GRACE_PERIOD`]).
+    pub fn shutdown_grace_period(mut self, grace_period: Duration) -> Self {
+        self.shutdown_grace_period = Some(grace_period);
+        self
+    }
+
+    /// Shut down gracefully on SIGTERM or SIGINT, as if a client had called
+    /// `Shutdown`. A further signal during the shutdown exits the process
+    /// immediately. Call [`crate::signals::block`] before starting any
+    /// other threads so that only the server sees the signals.
+    pub fn handle_signals(mut self, handle_signals: bool) -> Self {
+        self.handle_signals = handle_signals;
+        self
+    }
+
+    /// Serve with ttRPC's tokio-based async server rather than the
+    /// thread-based sync one. Requires the `async` feature. Only Unix and
+    /// vsock server URIs are supported: building the server fails with
+    /// [`Error::Usage`] for a TCP one.
+    pub fn use_async(mut self, use_async: bool) -> Self {
+        self.use_async = use_async;
+        self
+    }
+
+    /// Run `interceptor` around every method the server serves, after any
+    /// added before it. See [`InterceptorChain`].
+    pub fn interceptor<I: Interceptor + 'static>(mut self, interceptor: I) -> Self {
+        self.interceptors.push(Arc::new(interceptor));
+        self
+    }
+
+    /// File to write the bound vsock or TCP port to, once listening. If
+    /// there are several server URIs, the first vsock or TCP one is used.
+    pub fn port_file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.port_file = Some(path.as_ref().to_path_buf());
+        self
+    }
+
+    /// Install a child of `logger` as the global logger for as long as the
+    /// server exists.
+    pub fn logger(mut self, logger: &Logger) -> Self {
+        self.logger = Some(logger.clone());
+        self
+    }
+
+    /// Create the listening socket and register the service handlers. The
+    /// server does not accept connections until [`HelloServer::start`] is
+    /// called.
+    pub fn build(self) -> Result<HelloServer> {
+        let log_guard = self.logger.map(|l| {
+            slog_scope::set_global_logger(l.new(o!("subsystem" => "ttrpc", "type" => "server")))
+        });
+
+        if let Some(method) = self
+            .access_policy
+            .methods()
+            .into_iter()
+            .find(|m| !SERVICE_METHODS.contains(m))
+        {
+            return Err(Error::Usage(format!(
+                "access rule for unknown method {:?} (expected one of {:?})",
+                method, SERVICE_METHODS
+            )));
+        }
+
+        // Closed unless the build succeeds.
+        let activated = BoundListeners::adopt(systemd::listen_fds()?);
+        let activated_fds = activated.listeners();
+
+        let server_uris: Vec<&String> = self.server_uris.iter().filter(|u| !u.is_empty()).collect();
+
+        if server_uris.is_empty() && activated_fds.is_empty() {
+            return Err(Error::Usage("need server URI".to_string()));
+        }
+
+        info!(sl!(), "starting"; "socket-activated" => !activated_fds.is_empty());
+
+        let server_uris: Vec<ServerUri> = match activated_fds.is_empty() {
+            true => {
+                let mut uris = Vec::new();
+
+                for server_uri in server_uris {
+                    let mut uri: ServerUri = server_uri.parse()?;
+
+                    if self.force_abstract_socket {
+                        uri = uri.into_abstract();
+                    }
+
+                    uris.push(uri);
+                }
 
-        info!(sl!(), "requesting shutdown");
+                uris
+            }
+            false => {
+                if !server_uris.is_empty() {
+                    warn!(sl!(), "ignoring server URIs as socket activated";
+                        "server-uris" => format!("{:?}", server_uris));
+                }
 
-        let result = tx.send(true);
+                activated_fds.iter().map(|(_, uri)| uri.clone()).collect()
+            }
+        };
 
-        info!(sl!(), "requested shutdown"; "result" => format!("{:?}", result));
+        let (tx, rx) = channel::<ShutdownParams>();
 
-        if result.is_err() {
-            let mut status = Status::new();
+        let cfg = Config {
+            server_uri: server_uris[0].clone(),
+            interactive: false,
+            force_abstract_socket: self.force_abstract_socket,
+            abstract_naming: self.abstract_naming,
+            allow_remote_tcp: self.allow_remote_tcp,
+            access_policy: self.access_policy.clone(),
+            use_vsock_crate_for_vsock: false,
+            shutdown_grace_period: self
+                .shutdown_grace_period
+                .unwrap_or(DEFAULT_SHUTDOWN_GRACE_PERIOD),
+            tx: Some(tx.clone()),
+        };
 
-            // FIXME:
-            status.set_code(Code::NOT_FOUND);
-            status.set_message(format!("{:?}", result.err()));
+        let service = HelloService::new(cfg);
 
-            return Err(TError::RpcStatus(status));
+        if self.handle_signals {
+            signals::spawn_handler(tx.clone(), service.shutting_down.clone())?;
         }
 
-        Ok(ShutdownReply::new())
+        // Not serving until started.
+        let health = HealthService::new();
+
+        for name in &["", MY_SERVICE_NAME, HEALTH_SERVICE_NAME] {
+            health.set_status(name, ServingStatus::NOT_SERVING);
+        }
+
+        let reflection = ReflectionService::new(
+            &[
+                service::file_descriptor_proto(),
+                health::file_descriptor_proto(),
+                reflection::file_descriptor_proto(),
+            ],
+            &[
+                MY_SERVICE_NAME,
+                HEALTH_SERVICE_NAME,
+                REFLECTION_SERVICE_NAME,
+            ],
+        );
+
+        let svc_ref = service.cfg.clone();
+        let mut cfg = svc_ref.lock().unwrap();
+
+        let (listeners, bound_uris) = match activated_fds.is_empty() {
+            true => server_bind(&cfg, &server_uris)?,
+            false => (activated, server_uris.clone()),
+        };
+
+        let servers = match self.use_async {
+            false => Servers::Sync(sync_servers(
+                listeners.listeners(),
+                &service,
+                &health,
+                &reflection,
+                &self.interceptors,
+            )?),
+            true => async_servers(
+                listeners.listeners(),
+                &service,
+                &health,
+                &reflection,
+                &self.interceptors,
+            )?,
+        };
+
+        cfg.server_uri = bound_uris[0].clone();
+
+        if let Some(path) = &self.port_file {
+            write_port_file(path, &bound_uris)?;
+        }
+
+        // The servers own the listening sockets from here on.
+        listeners.disarm();
+
+        info!(sl!(), "setup complete";
+            "server-uris" => bound_uris
+                .iter()
+                .map(|u| u.to_string())
+                .collect::<Vec<String>>()
+                .join(" "));
+
+        // XXX: Critical - Allow the server handlers to access the
+        // XXX: shared data.
+        drop(cfg);
+
+        Ok(HelloServer {
+            service,
+            health,
+            servers,
+            server_uris: bound_uris,
+            rx,
+            _log_guard: log_guard,
+        })
     }
 }
 
-pub fn server(service: &HelloService, rx: Receiver<bool>) -> Result<()> {
-    info!(sl!(), "starting");
+/// A ttRPC server for `MyService` that runs until a client sends a
+/// `Shutdown` request (or [`HelloServer::shutdown_sender`] is used, or a
+/// signal is received if [`ServerBuilder::handle_signals`] is set). All of
+/// its listeners serve the same handlers and are shut down together.
+pub struct HelloServer {
+    service: HelloService,
+    health: HealthService,
+    servers: Servers,
+    server_uris: Vec<ServerUri>,
+    rx: Receiver<ShutdownParams>,
+    _log_guard: Option<GlobalLoggerGuard>,
+}
 
-    let s = Box::new(service.clone()) as Box<dyn MyService + Send + Sync>;
-    let s = Arc::new(s);
-    let the_service = create_my_service(s);
+impl HelloServer {
+    /// The first address the server is listening on, including any port
+    /// chosen by the kernel.
+    pub fn server_uri(&self) -> ServerUri {
+        self.service.cfg.lock().unwrap().server_uri.clone()
+    }
 
-    let svc_ref = service.cfg.clone();
-    let cfg = svc_ref.lock().unwrap();
+    /// Every address the server is listening on, in the order given.
+    pub fn server_uris(&self) -> &[ServerUri] {
+        &self.server_uris
+    }
 
-    // Valid format schemes:
-    //
-    // - 'unix:///path/to/socket'
-    //
-    // - 'vsock://cid:port'
-    //   (although CID is ignored as ttrpc hard-codes the cid
-    //   as libc::VMADDR_CID_ANY, hence, "vsock://-1:port").
-    let addr = &cfg.server_uri;
+    /// The statuses reported by the `Health` service. Every service is
+    /// reported as serving once the server starts, and as not serving once
+    /// it starts shutting down.
+    pub fn health(&self) -> &HealthService {
+        &self.health
+    }
 
-    let mut server = Server::new()
-        .bind(&addr)
-        .unwrap()
-        .register_service(the_service);
+    /// Returns a channel that can be used to request the server shut down,
+    /// exactly as the `Shutdown` RPC does. A `grace_period` of `None` uses
+    /// the server's default.
+    pub fn shutdown_sender(&self) -> Option<Sender<ShutdownParams>> {
+        self.service.cfg.lock().unwrap().tx.clone()
+    }
 
-    info!(sl!(), "setup complete"; "server-uri" => addr);
+    /// Start accepting connections in the background.
+    pub fn start(&mut self) -> Result<()> {
+        self.servers.start()?;
 
-    // XXX: Critical - Allow the server handlers to access the
-    // XXX: shared data.
-    drop(cfg);
+        info!(sl!(), "started");
 
-    server.start().expect("failed to start server");
+        for name in &["", MY_SERVICE_NAME, HEALTH_SERVICE_NAME] {
+            self.health.set_status(name, ServingStatus::SERVING);
+        }
 
-    info!(sl!(), "started");
+        // Keep serving regardless, since the listeners are already live.
+        if let Err(e) = systemd::notify(NOTIFY_READY) {
+            warn!(sl!(), "failed to notify service manager";
+                "error" => e.to_string());
+        }
 
-    info!(sl!(), "Waiting for server shutdown request");
+        Ok(())
+    }
 
-    let _ = rx.recv();
+    // Wait up to "grace_period" for in-flight requests to finish,
+    // returning the number still running. Returns early if a forced
+    // shutdown is requested meanwhile.
+    fn drain(&self, grace_period: Duration) -> usize {
+        let deadline = Instant::now() + grace_period;
 
-    info!(sl!(), "Waited for server shutdown request");
+        loop {
+            let pending = self.service.in_flight.load(Ordering::SeqCst);
 
-    info!(sl!(), "Waiting for ttRPC server to end");
-    server.shutdown();
-    info!(sl!(), "Waited for ttRPC server to end");
+            if pending == 0 || Instant::now() >= deadline {
+                return pending;
+            }
 
-    Ok(())
+            match self.rx.recv_timeout(DRAIN_POLL_INTERVAL) {
+                Ok(params) if params.force => {
+                    info!(sl!(), "forced shutdown requested";
+                        "reason" => params.reason);
+
+                    return self.service.in_flight.load(Ordering::SeqCst);
+                }
+                Ok(_) | Err(RecvTimeoutError::Timeout) => (),
+                Err(RecvTimeoutError::Disconnected) => thread::sleep(DRAIN_POLL_INTERVAL),
+            }
+        }
+    }
+
+    // Wait for Shutdown requests, including any that asked for this
+    // shutdown, to hand over their replies.
+    fn wait_for_shutdown_replies(&self) {
+        let deadline = Instant::now() + SHUTDOWN_REPLY_TIMEOUT;
+
+        while self.service.shutdowns_in_flight.load(Ordering::SeqCst) > 0 {
+            if Instant::now() >= deadline {
+                warn!(sl!(), "abandoning shutdown replies");
+                return;
+            }
+
+            thread::sleep(DRAIN_POLL_INTERVAL);
+        }
+    }
+
+    /// Block until a shutdown is requested, then stop the server once
+    /// in-flight requests have finished or the grace period has expired.
+    /// Returns what requested the shutdown.
+    pub fn wait(self) -> Result<ShutdownTrigger> {
+        info!(sl!(), "Waiting for server shutdown request");
+
+        let params = self.rx.recv().unwrap_or_default();
+
+        // Reject new requests, if a shutdown_sender() caller did not go
+        // through the Shutdown handler.
+        self.service.shutting_down.store(true, Ordering::SeqCst);
+
+        self.health.set_all_not_serving();
+
+        let grace_period = match params.force {
+            true => Duration::from_secs(0),
+            false => params
+                .grace_period
+                .unwrap_or(self.service.cfg.lock().unwrap().shutdown_grace_period),
+        };
+
+        info!(sl!(), "Waited for server shutdown request";
+            "trigger" => params.trigger.to_string(),
+            "reason" => params.reason.clone(),
+            "grace-period" => format!("{:?}", grace_period),
+            "force" => params.force);
+
+        info!(sl!(), "Waiting for ttRPC server to end");
+
+        // Stop regardless, since the shutdown was requested.
+        if let Err(e) = systemd::notify(NOTIFY_STOPPING) {
+            warn!(sl!(), "failed to notify service manager";
+                "error" => e.to_string());
+        }
+
+        let pending = self.drain(grace_period);
+
+        if pending > 0 {
+            warn!(sl!(), "abandoning in-flight requests"; "count" => pending);
+        }
+
+        self.wait_for_shutdown_replies();
+
+        self.servers.shutdown();
+        info!(sl!(), "Waited for ttRPC server to end";
+            "trigger" => params.trigger.to_string());
+
+        Ok(params.trigger)
+    }
+
+    /// Start the server and serve requests until a shutdown is requested,
+    /// returning what requested it.
+    pub fn run(mut self) -> Result<ShutdownTrigger> {
+        self.start()?;
+        self.wait()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::process;
+    use tempfile::TempDir;
+
+    fn test_config() -> Config {
+        Config {
+            server_uri: ServerUri::UnixAbstract(String::new()),
+            interactive: false,
+            force_abstract_socket: false,
+            abstract_naming: AbstractNaming::Auto,
+            allow_remote_tcp: false,
+            use_vsock_crate_for_vsock: false,
+            access_policy: AccessPolicy::new(),
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            tx: None,
+        }
+    }
+
+    #[test]
+    fn test_server_bind_cleans_up_on_error() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("server.sock");
+
+        let name = format!("test-protobuf-ttrpc-{}-bind-cleanup", process::id());
+
+        let server_uris = vec![
+            ServerUri::UnixPath(path.to_str().unwrap().to_string()),
+            ServerUri::UnixAbstract(name.clone()),
+            // Cannot be listened on.
+            ServerUri::HybridVsock {
+                path: path.to_str().unwrap().to_string(),
+                port: 1024,
+            },
+        ];
+
+        assert!(server_bind(&test_config(), &server_uris).is_err());
+
+        assert!(!path.exists(), "socket file not removed");
+
+        // The abstract sockets were closed, so can be bound again.
+        let fds = server_create_abstract_fds(&name, AbstractNaming::Auto).unwrap();
+
+        for fd in fds {
+            let _ = close(fd);
+        }
+    }
+
+    #[test]
+    fn test_server_build_cleans_up_on_error() {
+        let _lock = systemd::TEST_ENV_LOCK.lock().unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("server.sock");
+
+        // The port file cannot be written, as its directory does not exist.
+        let result = ServerBuilder::new()
+            .server_uri(&format!("unix://{}", path.display()))
+            .server_uri("tcp://127.0.0.1:0")
+            .port_file(dir.path().join("missing").join("port"))
+            .build();
+
+        match result {
+            Err(Error::Io(_)) => (),
+            Err(e) => panic!("expected port file error, got {:?}", e),
+            Ok(_) => panic!("expected port file error"),
+        }
+
+        assert!(!path.exists(), "socket file not removed");
+    }
+
+    #[test]
+    fn test_server_bind() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("server.sock");
+
+        let server_uris = vec![ServerUri::UnixPath(path.to_str().unwrap().to_string())];
+
+        let (listeners, bound_uris) = server_bind(&test_config(), &server_uris).unwrap();
+
+        assert_eq!(listeners.listeners().len(), 1);
+        assert_eq!(bound_uris, server_uris);
+        assert!(path.exists());
+
+        drop(listeners);
+
+        assert!(!path.exists(), "socket file not removed");
+    }
 }
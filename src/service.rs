@@ -354,6 +354,10 @@ impl ::protobuf::reflect::ProtobufValue for HelloReply {
 
 #[derive(PartialEq,Clone,Default)]
 pub struct ShutdownRequest {
+    // message fields
+    pub reason: ::std::string::String,
+    pub grace_period_ms: u64,
+    pub force: bool,
     // special fields
     pub unknown_fields: ::protobuf::UnknownFields,
     pub cached_size: ::protobuf::CachedSize,
@@ -369,6 +373,62 @@ impl ShutdownRequest {
     pub fn new() -> ShutdownRequest {
         ::std::default::Default::default()
     }
+
+    // string reason = 1;
+
+
+    pub fn get_reason(&self) -> &str {
+        &self.reason
+    }
+    pub fn clear_reason(&mut self) {
+        self.reason.clear();
+    }
+
+    // Param is passed by value, moved
+    pub fn set_reason(&mut self, v: ::std::string::String) {
+        self.reason = v;
+    }
+
+    // Mutable pointer to the field.
+    // If field is not initialized, it is initialized with default value first.
+    pub fn mut_reason(&mut self) -> &mut ::std::string::String {
+        &mut self.reason
+    }
+
+    // Take field
+    pub fn take_reason(&mut self) -> ::std::string::String {
+        ::std::mem::replace(&mut self.reason, ::std::string::String::new())
+    }
+
+    // uint64 grace_period_ms = 2;
+
+
+    pub fn get_grace_period_ms(&self) -> u64 {
+        self.grace_period_ms
+    }
+    pub fn clear_grace_period_ms(&mut self) {
+        self.grace_period_ms = 0;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_grace_period_ms(&mut self, v: u64) {
+        self.grace_period_ms = v;
+    }
+
+    // bool force = 3;
+
+
+    pub fn get_force(&self) -> bool {
+        self.force
+    }
+    pub fn clear_force(&mut self) {
+        self.force = false;
+    }
+
+    // Param is passed by value, moved
+    pub fn set_force(&mut self, v: bool) {
+        self.force = v;
+    }
 }
 
 impl ::protobuf::Message for ShutdownRequest {
@@ -380,6 +440,23 @@ impl ::protobuf::Message for ShutdownRequest {
         while !is.eof()? {
             let (field_number, wire_type) = is.read_tag_unpack()?;
             match field_number {
+                1 => {
+                    ::protobuf::rt::read_singular_proto3_string_into(wire_type, is, &mut self.reason)?;
+                },
+                2 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_uint64()?;
+                    self.grace_period_ms = tmp;
+                },
+                3 => {
+                    if wire_type != ::protobuf::wire_format::WireTypeVarint {
+                        return ::std::result::Result::Err(::protobuf::rt::unexpected_wire_type(wire_type));
+                    }
+                    let tmp = is.read_bool()?;
+                    self.force = tmp;
+                },
                 _ => {
                     ::protobuf::rt::read_unknown_or_skip_group(field_number, wire_type, is, self.mut_unknown_fields())?;
                 },
@@ -392,12 +469,30 @@ impl ::protobuf::Message for ShutdownRequest {
     #[allow(unused_variables)]
     fn compute_size(&self) -> u32 {
         let mut my_size = 0;
+        if !self.reason.is_empty() {
+            my_size += ::protobuf::rt::string_size(1, &self.reason);
+        }
+        if self.grace_period_ms != 0 {
+            my_size += ::protobuf::rt::value_size(2, self.grace_period_ms, ::protobuf::wire_format::WireTypeVarint);
+        }
+        if self.force != false {
+            my_size += 2;
+        }
         my_size += ::protobuf::rt::unknown_fields_size(self.get_unknown_fields());
         self.cached_size.set(my_size);
         my_size
     }
 
     fn write_to_with_cached_sizes(&self, os: &mut ::protobuf::CodedOutputStream<'_>) -> ::protobuf::ProtobufResult<()> {
+        if !self.reason.is_empty() {
+            os.write_string(1, &self.reason)?;
+        }
+        if self.grace_period_ms != 0 {
+            os.write_uint64(2, self.grace_period_ms)?;
+        }
+        if self.force != false {
+            os.write_bool(3, self.force)?;
+        }
         os.write_unknown_fields(self.get_unknown_fields())?;
         ::std::result::Result::Ok(())
     }
@@ -436,7 +531,22 @@ impl ::protobuf::Message for ShutdownRequest {
         static mut descriptor: ::protobuf::lazy::Lazy<::protobuf::reflect::MessageDescriptor> = ::protobuf::lazy::Lazy::INIT;
         unsafe {
             descriptor.get(|| {
-                let fields = ::std::vec::Vec::new();
+                let mut fields = ::std::vec::Vec::new();
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeString>(
+                    "reason",
+                    |m: &ShutdownRequest| { &m.reason },
+                    |m: &mut ShutdownRequest| { &mut m.reason },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeUint64>(
+                    "grace_period_ms",
+                    |m: &ShutdownRequest| { &m.grace_period_ms },
+                    |m: &mut ShutdownRequest| { &mut m.grace_period_ms },
+                ));
+                fields.push(::protobuf::reflect::accessor::make_simple_field_accessor::<_, ::protobuf::types::ProtobufTypeBool>(
+                    "force",
+                    |m: &ShutdownRequest| { &m.force },
+                    |m: &mut ShutdownRequest| { &mut m.force },
+                ));
                 ::protobuf::reflect::MessageDescriptor::new_pb_name::<ShutdownRequest>(
                     "ShutdownRequest",
                     fields,
@@ -456,6 +566,9 @@ impl ::protobuf::Message for ShutdownRequest {
 
 impl ::protobuf::Clear for ShutdownRequest {
     fn clear(&mut self) {
+        self.reason.clear();
+        self.grace_period_ms = 0;
+        self.force = false;
         self.unknown_fields.clear();
     }
 }
@@ -638,31 +751,46 @@ impl ::protobuf::reflect::ProtobufValue for ShutdownReply {
 static file_descriptor_proto_data: &'static [u8] = b"\
     \n\rservice.proto\x12\x07service\"\"\n\x0cHelloRequest\x12\x12\n\x04name\
     \x18\x01\x20\x01(\tR\x04name\"&\n\nHelloReply\x12\x18\n\x07message\x18\
-    \x01\x20\x01(\tR\x07message\"\x11\n\x0fShutdownRequest\")\n\rShutdownRep\
-    ly\x12\x18\n\x07message\x18\x01\x20\x01(\tR\x07message2\x85\x01\n\tMySer\
-    vice\x128\n\x08SayHello\x12\x15.service.HelloRequest\x1a\x13.service.Hel\
-    loReply\"\0\x12>\n\x08Shutdown\x12\x18.service.ShutdownRequest\x1a\x16.s\
-    ervice.ShutdownReply\"\0J\xa7\x03\n\x06\x12\x04\0\0\x16\x01\n\x08\n\x01\
-    \x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\x12\x03\x02\0\x10\n\n\n\x02\x04\0\
-    \x12\x04\x04\0\x06\x01\n\n\n\x03\x04\0\x01\x12\x03\x04\x08\x14\n\x0b\n\
-    \x04\x04\0\x02\0\x12\x03\x05\x02\x12\n\x0c\n\x05\x04\0\x02\0\x05\x12\x03\
-    \x05\x02\x08\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\x05\t\r\n\x0c\n\x05\x04\
-    \0\x02\0\x03\x12\x03\x05\x10\x11\n\n\n\x02\x04\x01\x12\x04\x08\0\n\x01\n\
-    \n\n\x03\x04\x01\x01\x12\x03\x08\x08\x12\n\x0b\n\x04\x04\x01\x02\0\x12\
-    \x03\t\x02\x15\n\x0c\n\x05\x04\x01\x02\0\x05\x12\x03\t\x02\x08\n\x0c\n\
-    \x05\x04\x01\x02\0\x01\x12\x03\t\t\x10\n\x0c\n\x05\x04\x01\x02\0\x03\x12\
-    \x03\t\x13\x14\n\n\n\x02\x04\x02\x12\x04\x0c\0\r\x01\n\n\n\x03\x04\x02\
-    \x01\x12\x03\x0c\x08\x17\n\n\n\x02\x04\x03\x12\x04\x0f\0\x11\x01\n\n\n\
-    \x03\x04\x03\x01\x12\x03\x0f\x08\x15\n\x0b\n\x04\x04\x03\x02\0\x12\x03\
-    \x10\x02\x15\n\x0c\n\x05\x04\x03\x02\0\x05\x12\x03\x10\x02\x08\n\x0c\n\
-    \x05\x04\x03\x02\0\x01\x12\x03\x10\t\x10\n\x0c\n\x05\x04\x03\x02\0\x03\
-    \x12\x03\x10\x13\x14\n\n\n\x02\x06\0\x12\x04\x13\0\x16\x01\n\n\n\x03\x06\
-    \0\x01\x12\x03\x13\x08\x11\n\x0b\n\x04\x06\0\x02\0\x12\x03\x14\x025\n\
-    \x0c\n\x05\x06\0\x02\0\x01\x12\x03\x14\x06\x0e\n\x0c\n\x05\x06\0\x02\0\
-    \x02\x12\x03\x14\x10\x1c\n\x0c\n\x05\x06\0\x02\0\x03\x12\x03\x14'1\n\x0b\
-    \n\x04\x06\0\x02\x01\x12\x03\x15\x02;\n\x0c\n\x05\x06\0\x02\x01\x01\x12\
-    \x03\x15\x06\x0e\n\x0c\n\x05\x06\0\x02\x01\x02\x12\x03\x15\x10\x1f\n\x0c\
-    \n\x05\x06\0\x02\x01\x03\x12\x03\x15*7b\x06proto3\
+    \x01\x20\x01(\tR\x07message\"g\n\x0fShutdownRequest\x12\x16\n\x06reason\
+    \x18\x01\x20\x01(\tR\x06reason\x12&\n\x0fgrace_period_ms\x18\x02\x20\x01\
+    (\x04R\rgracePeriodMs\x12\x14\n\x05force\x18\x03\x20\x01(\x08R\x05force\
+    \")\n\rShutdownReply\x12\x18\n\x07message\x18\x01\x20\x01(\tR\x07message\
+    2\x85\x01\n\tMyService\x128\n\x08SayHello\x12\x15.service.HelloRequest\
+    \x1a\x13.service.HelloReply\"\0\x12>\n\x08Shutdown\x12\x18.service.Shutd\
+    ownRequest\x1a\x16.service.ShutdownReply\"\0J\x9d\x06\n\x06\x12\x04\0\0\
+    \x1f\x01\n\x08\n\x01\x0c\x12\x03\0\0\x12\n\x08\n\x01\x02\x12\x03\x02\0\
+    \x10\n\n\n\x02\x04\0\x12\x04\x04\0\x06\x01\n\n\n\x03\x04\0\x01\x12\x03\
+    \x04\x08\x14\n\x0b\n\x04\x04\0\x02\0\x12\x03\x05\x02\x12\n\x0c\n\x05\x04\
+    \0\x02\0\x05\x12\x03\x05\x02\x08\n\x0c\n\x05\x04\0\x02\0\x01\x12\x03\x05\
+    \t\r\n\x0c\n\x05\x04\0\x02\0\x03\x12\x03\x05\x10\x11\n\n\n\x02\x04\x01\
+    \x12\x04\x08\0\n\x01\n\n\n\x03\x04\x01\x01\x12\x03\x08\x08\x12\n\x0b\n\
+    \x04\x04\x01\x02\0\x12\x03\t\x02\x15\n\x0c\n\x05\x04\x01\x02\0\x05\x12\
+    \x03\t\x02\x08\n\x0c\n\x05\x04\x01\x02\0\x01\x12\x03\t\t\x10\n\x0c\n\x05\
+    \x04\x01\x02\0\x03\x12\x03\t\x13\x14\n\n\n\x02\x04\x02\x12\x04\x0c\0\x16\
+    \x01\n\n\n\x03\x04\x02\x01\x12\x03\x0c\x08\x17\nH\n\x04\x04\x02\x02\0\
+    \x12\x03\x0e\x02\x14\x1a;\x20Why\x20the\x20server\x20is\x20being\x20shut\
+    \x20down\x20(logged\x20by\x20the\x20server).\n\n\x0c\n\x05\x04\x02\x02\0\
+    \x05\x12\x03\x0e\x02\x08\n\x0c\n\x05\x04\x02\x02\0\x01\x12\x03\x0e\t\x0f\
+    \n\x0c\n\x05\x04\x02\x02\0\x03\x12\x03\x0e\x12\x13\nj\n\x04\x04\x02\x02\
+    \x01\x12\x03\x12\x02\x1d\x1a]\x20How\x20long\x20to\x20let\x20in-flight\
+    \x20requests\x20finish,\x20in\x20milliseconds\n\x20(0\x20means\x20the\
+    \x20server's\x20default).\n\n\x0c\n\x05\x04\x02\x02\x01\x05\x12\x03\x12\
+    \x02\x08\n\x0c\n\x05\x04\x02\x02\x01\x01\x12\x03\x12\t\x18\n\x0c\n\x05\
+    \x04\x02\x02\x01\x03\x12\x03\x12\x1b\x1c\n@\n\x04\x04\x02\x02\x02\x12\
+    \x03\x15\x02\x11\x1a3\x20Shut\x20down\x20without\x20waiting\x20for\x20in\
+    -flight\x20requests.\n\n\x0c\n\x05\x04\x02\x02\x02\x05\x12\x03\x15\x02\
+    \x06\n\x0c\n\x05\x04\x02\x02\x02\x01\x12\x03\x15\x07\x0c\n\x0c\n\x05\x04\
+    \x02\x02\x02\x03\x12\x03\x15\x0f\x10\n\n\n\x02\x04\x03\x12\x04\x18\0\x1a\
+    \x01\n\n\n\x03\x04\x03\x01\x12\x03\x18\x08\x15\n\x0b\n\x04\x04\x03\x02\0\
+    \x12\x03\x19\x02\x15\n\x0c\n\x05\x04\x03\x02\0\x05\x12\x03\x19\x02\x08\n\
+    \x0c\n\x05\x04\x03\x02\0\x01\x12\x03\x19\t\x10\n\x0c\n\x05\x04\x03\x02\0\
+    \x03\x12\x03\x19\x13\x14\n\n\n\x02\x06\0\x12\x04\x1c\0\x1f\x01\n\n\n\x03\
+    \x06\0\x01\x12\x03\x1c\x08\x11\n\x0b\n\x04\x06\0\x02\0\x12\x03\x1d\x025\
+    \n\x0c\n\x05\x06\0\x02\0\x01\x12\x03\x1d\x06\x0e\n\x0c\n\x05\x06\0\x02\0\
+    \x02\x12\x03\x1d\x10\x1c\n\x0c\n\x05\x06\0\x02\0\x03\x12\x03\x1d'1\n\x0b\
+    \n\x04\x06\0\x02\x01\x12\x03\x1e\x02;\n\x0c\n\x05\x06\0\x02\x01\x01\x12\
+    \x03\x1e\x06\x0e\n\x0c\n\x05\x06\0\x02\x01\x02\x12\x03\x1e\x10\x1f\n\x0c\
+    \n\x05\x06\0\x02\x01\x03\x12\x03\x1e*7b\x06proto3\
 ";
 
 static mut file_descriptor_proto_lazy: ::protobuf::lazy::Lazy<::protobuf::descriptor::FileDescriptorProto> = ::protobuf::lazy::Lazy::INIT;
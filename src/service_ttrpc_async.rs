@@ -0,0 +1,90 @@
+// This file is generated by ttrpc-compiler 0.4.0. Do not edit
+// @generated
+
+// https://github.com/Manishearth/rust-clippy/issues/702
+#![allow(unknown_lints)]
+#![allow(clipto_camel_casepy)]
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+#![allow(box_pointers)]
+#![allow(dead_code)]
+#![allow(missing_docs)]
+#![allow(non_camel_case_types)]
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(trivial_casts)]
+#![allow(unsafe_code)]
+#![allow(unused_imports)]
+#![allow(unused_results)]
+use protobuf::{CodedInputStream, CodedOutputStream, Message};
+use std::collections::HashMap;
+use std::sync::Arc;
+use async_trait::async_trait;
+
+#[derive(Clone)]
+pub struct MyServiceClient {
+    client: ::ttrpc::r#async::Client,
+}
+
+impl MyServiceClient {
+    pub fn new(client: ::ttrpc::r#async::Client) -> Self {
+        MyServiceClient {
+            client: client,
+        }
+    }
+
+    pub async fn say_hello(&self, req: &super::service::HelloRequest, timeout_nano: i64) -> ::ttrpc::Result<super::service::HelloReply> {
+        let mut cres = super::service::HelloReply::new();
+        ::ttrpc::async_client_request!(self, req, timeout_nano, "service.MyService", "SayHello", cres);
+    }
+
+    pub async fn shutdown(&self, req: &super::service::ShutdownRequest, timeout_nano: i64) -> ::ttrpc::Result<super::service::ShutdownReply> {
+        let mut cres = super::service::ShutdownReply::new();
+        ::ttrpc::async_client_request!(self, req, timeout_nano, "service.MyService", "Shutdown", cres);
+    }
+}
+
+struct SayHelloMethod {
+    service: Arc<std::boxed::Box<dyn MyService + Send + Sync>>,
+}
+
+#[async_trait]
+impl ::ttrpc::r#async::MethodHandler for SayHelloMethod {
+    async fn handler(&self, ctx: ::ttrpc::r#async::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<(u32, Vec<u8>)> {
+        ::ttrpc::async_request_handler!(self, ctx, req, service, HelloRequest, say_hello);
+    }
+}
+
+struct ShutdownMethod {
+    service: Arc<std::boxed::Box<dyn MyService + Send + Sync>>,
+}
+
+#[async_trait]
+impl ::ttrpc::r#async::MethodHandler for ShutdownMethod {
+    async fn handler(&self, ctx: ::ttrpc::r#async::TtrpcContext, req: ::ttrpc::Request) -> ::ttrpc::Result<(u32, Vec<u8>)> {
+        ::ttrpc::async_request_handler!(self, ctx, req, service, ShutdownRequest, shutdown);
+    }
+}
+
+#[async_trait]
+pub trait MyService: Sync {
+    async fn say_hello(&self, _ctx: &::ttrpc::r#async::TtrpcContext, _req: super::service::HelloRequest) -> ::ttrpc::Result<super::service::HelloReply> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/service.MyService/SayHello is not supported".to_string())))
+    }
+    async fn shutdown(&self, _ctx: &::ttrpc::r#async::TtrpcContext, _req: super::service::ShutdownRequest) -> ::ttrpc::Result<super::service::ShutdownReply> {
+        Err(::ttrpc::Error::RpcStatus(::ttrpc::get_status(::ttrpc::Code::NOT_FOUND, "/service.MyService/Shutdown is not supported".to_string())))
+    }
+}
+
+pub fn create_my_service(service: Arc<std::boxed::Box<dyn MyService + Send + Sync>>) -> HashMap <String, Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>> {
+    let mut methods = HashMap::new();
+
+    methods.insert("/service.MyService/SayHello".to_string(),
+                    std::boxed::Box::new(SayHelloMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>);
+
+    methods.insert("/service.MyService/Shutdown".to_string(),
+                    std::boxed::Box::new(ShutdownMethod{service: service.clone()}) as std::boxed::Box<dyn ::ttrpc::r#async::MethodHandler + Send + Sync>);
+
+    methods
+}
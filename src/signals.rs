@@ -0,0 +1,95 @@
+// Description: Turn SIGTERM and SIGINT into server shutdown requests
+
+use crate::error::{exit_code, Error, Result};
+use crate::types::{ShutdownParams, ShutdownTrigger};
+use nix::sys::signal::{SigSet, Signal};
+
+use slog::{error, info, warn};
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
+
+const SHUTDOWN_SIGNALS: &[Signal] = &[Signal::SIGTERM, Signal::SIGINT];
+
+fn shutdown_sigset() -> SigSet {
+    let mut mask = SigSet::empty();
+
+    for sig in SHUTDOWN_SIGNALS {
+        mask.add(*sig);
+    }
+
+    mask
+}
+
+/// Block SIGTERM and SIGINT in the calling thread, and so in every thread
+/// it creates afterwards, so they can be handled by
+/// [`crate::ServerBuilder::handle_signals`].
+///
+/// Call this before starting any other threads (including the logger's),
+/// otherwise a signal may be delivered to a thread that does not block it
+/// and terminate the process.
+pub fn block() -> Result<()> {
+    match shutdown_sigset().thread_block() {
+        Ok(_) => Ok(()),
+        Err(e) => Err(Error::Io(format!(
+            "failed to block shutdown signals: {:?}",
+            e
+        ))),
+    }
+}
+
+// Wait for shutdown signals on a dedicated thread. The first one asks the
+// server to shut down gracefully by sending to "tx", exactly as the
+// Shutdown RPC does. A signal received once a shutdown is already in
+// progress ("shutting_down" is set) exits the process immediately.
+pub(crate) fn spawn_handler(
+    tx: Sender<ShutdownParams>,
+    shutting_down: Arc<AtomicBool>,
+) -> Result<()> {
+    block()?;
+
+    let mask = shutdown_sigset();
+
+    thread::spawn(move || loop {
+        let sig = match mask.wait() {
+            Ok(sig) => sig,
+            Err(e) => {
+                error!(sl!(), "failed to wait for signals"; "error" => format!("{:?}", e));
+                return;
+            }
+        };
+
+        if shutting_down.load(Ordering::SeqCst) {
+            warn!(sl!(), "signal received during shutdown, exiting immediately";
+                "signal" => format!("{:?}", sig));
+
+            process::exit(exit_code::SIGNAL_BASE + sig as i32);
+        }
+
+        info!(sl!(), "signal received, requesting shutdown";
+            "signal" => format!("{:?}", sig));
+
+        let trigger = ShutdownTrigger::Signal(sig);
+
+        let params = ShutdownParams {
+            trigger,
+            reason: format!("received {:?}", sig),
+            ..Default::default()
+        };
+
+        // Set here too, so a second signal forces an exit even if the
+        // server has not yet seen the request.
+        shutting_down.store(true, Ordering::SeqCst);
+
+        if tx.send(params).is_err() {
+            warn!(sl!(), "server no longer waiting for shutdown, exiting";
+                "signal" => format!("{:?}", sig));
+
+            process::exit(trigger.exit_code());
+        }
+    });
+
+    Ok(())
+}
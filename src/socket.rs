@@ -0,0 +1,146 @@
+// Description: Socket helpers shared by the client and server
+
+use crate::error::{Error, Result};
+use nix::sys::socket::{
+    bind, listen, socket, AddressFamily, SockAddr, SockFlag, SockType, UnixAddr,
+};
+use nix::unistd::close;
+use std::fmt;
+use std::os::unix::io::RawFd;
+use std::str::FromStr;
+
+// Maximum number of pending connections for a listening socket
+// (the value used by the "ttrpc" crate).
+const SOCKET_BACKLOG: usize = 10;
+
+/// How the name of an abstract Unix socket is encoded in its address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AbstractNaming {
+    /// The name has a trailing NUL byte, as created by the "ttrpc" crate.
+    #[default]
+    TtrpcNul,
+
+    /// The name is used exactly, as created by most C and Go programs.
+    Exact,
+
+    /// Try `TtrpcNul`, then `Exact` when connecting, and bind both names
+    /// when listening.
+    Auto,
+}
+
+const TTRPC_NUL_NAMING: &str = "ttrpc-nul";
+const EXACT_NAMING: &str = "exact";
+const AUTO_NAMING: &str = "auto";
+
+/// Names accepted by [`AbstractNaming::from_str`].
+pub const ABSTRACT_NAMING_NAMES: &[&str] = &[TTRPC_NUL_NAMING, EXACT_NAMING, AUTO_NAMING];
+
+impl AbstractNaming {
+    /// The concrete naming conventions to use, in order.
+    pub fn conventions(self) -> &'static [AbstractNaming] {
+        match self {
+            AbstractNaming::TtrpcNul => &[AbstractNaming::TtrpcNul],
+            AbstractNaming::Exact => &[AbstractNaming::Exact],
+            AbstractNaming::Auto => &[AbstractNaming::TtrpcNul, AbstractNaming::Exact],
+        }
+    }
+}
+
+impl FromStr for AbstractNaming {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            TTRPC_NUL_NAMING => Ok(AbstractNaming::TtrpcNul),
+            EXACT_NAMING => Ok(AbstractNaming::Exact),
+            AUTO_NAMING => Ok(AbstractNaming::Auto),
+            _ => Err(Error::Usage(format!(
+                "invalid abstract socket naming {:?} (expected one of {:?})",
+                s, ABSTRACT_NAMING_NAMES
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for AbstractNaming {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            AbstractNaming::TtrpcNul => TTRPC_NUL_NAMING,
+            AbstractNaming::Exact => EXACT_NAMING,
+            AbstractNaming::Auto => AUTO_NAMING,
+        };
+
+        write!(f, "{}", name)
+    }
+}
+
+// Returns the on-the-wire name of an abstract socket (excluding the leading
+// NUL byte the kernel uses to denote an abstract socket). "Auto" is treated
+// as "TtrpcNul"; callers wanting both should iterate over
+// AbstractNaming::conventions().
+pub fn abstract_socket_name(name: &str, naming: AbstractNaming) -> String {
+    match naming {
+        // XXX: ESSENTIAL! Add a trailing terminator
+        // XXX: as this is what the "ttrpc" crate does!
+        AbstractNaming::TtrpcNul | AbstractNaming::Auto => name.to_string() + "\x00",
+        AbstractNaming::Exact => name.to_string(),
+    }
+}
+
+// Create the address for the abstract socket called "name".
+pub fn abstract_sock_addr(name: &str, naming: AbstractNaming) -> Result<SockAddr> {
+    let path = abstract_socket_name(name, naming);
+
+    let mut unix_addr = match UnixAddr::new_abstract(path.as_bytes()) {
+        Ok(s) => s,
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "Failed to create Unix Domain abstract socket: {:?}",
+                e
+            )))
+        }
+    };
+
+    // FIXME: Fix BUG: https://github.com/nix-rust/nix/pull/1120/
+    //
+    // All versions of the nix package prior to 0.16.0 contained a bug
+    // where the length of the path specified to new_abstract() was not
+    // calculated - nix assumed the maximum path length. This meant the
+    // path contained trailing nulls which is perfectly valid for an
+    // abstract socket... it just means you cannot connect to it from
+    // well-behaved programs that correctly handle the path length!!
+    //
+    // The problem for this program is that it currently relies on the
+    // vsock crate, which depends on "nix = 0.15.1".
+    unix_addr.1 = path.len() + 1;
+
+    Ok(SockAddr::Unix(unix_addr))
+}
+
+// Create a socket bound to "sock_addr" that is listening for connections.
+pub fn listen_fd(family: AddressFamily, sock_addr: &SockAddr) -> Result<RawFd> {
+    let fd = match socket(family, SockType::Stream, SockFlag::SOCK_CLOEXEC, None) {
+        Ok(fd) => fd,
+        Err(e) => return Err(Error::Socket(format!("Failed to create socket: {:?}", e))),
+    };
+
+    if let Err(e) = bind(fd, sock_addr) {
+        let _ = close(fd);
+
+        return Err(Error::Socket(format!(
+            "Failed to bind socket to {}: {:?}",
+            sock_addr, e
+        )));
+    }
+
+    if let Err(e) = listen(fd, SOCKET_BACKLOG) {
+        let _ = close(fd);
+
+        return Err(Error::Socket(format!(
+            "Failed to listen on socket {}: {:?}",
+            sock_addr, e
+        )));
+    }
+
+    Ok(fd)
+}
@@ -0,0 +1,310 @@
+// Description: systemd socket activation and readiness notification
+
+use crate::error::{Error, Result};
+use crate::socket::{abstract_sock_addr, AbstractNaming};
+use crate::uri::ServerUri;
+use nix::fcntl::{fcntl, FcntlArg, FdFlag};
+use nix::sys::socket::{
+    getsockname, sendto, socket, AddressFamily, MsgFlags, SockAddr, SockFlag, SockType, UnixAddr,
+};
+use nix::unistd::{close, getpid};
+
+use slog::info;
+use std::env;
+use std::os::unix::io::RawFd;
+
+// The first file descriptor passed by systemd (sd_listen_fds(3)).
+const SD_LISTEN_FDS_START: RawFd = 3;
+
+const LISTEN_PID_ENV: &str = "LISTEN_PID";
+const LISTEN_FDS_ENV: &str = "LISTEN_FDS";
+const LISTEN_FDNAMES_ENV: &str = "LISTEN_FDNAMES";
+const NOTIFY_SOCKET_ENV: &str = "NOTIFY_SOCKET";
+
+/// Readiness notification sent once the server is accepting connections.
+pub const NOTIFY_READY: &str = "READY=1";
+
+/// Readiness notification sent when the server starts shutting down.
+pub const NOTIFY_STOPPING: &str = "STOPPING=1";
+
+// Returns the address a listening socket passed by systemd is bound to,
+// expressed as a server URI. Only Unix and vsock sockets are supported.
+fn listen_fd_uri(fd: RawFd) -> Result<ServerUri> {
+    let addr = match getsockname(fd) {
+        Ok(a) => a,
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "failed to query address of passed socket {}: {:?}",
+                fd, e
+            )))
+        }
+    };
+
+    match addr {
+        SockAddr::Vsock(addr) => Ok(ServerUri::Vsock {
+            cid: addr.cid(),
+            port: addr.port(),
+        }),
+        SockAddr::Unix(addr) => {
+            if let Some(path) = addr.path() {
+                return Ok(ServerUri::UnixPath(path.to_string_lossy().to_string()));
+            }
+
+            match addr.as_abstract() {
+                // Report the name without any trailing NUL ("ttrpc"
+                // naming), as the "@name" form of the URI expects.
+                Some(name) => Ok(ServerUri::UnixAbstract(
+                    String::from_utf8_lossy(name)
+                        .trim_end_matches('\0')
+                        .to_string(),
+                )),
+                None => Err(Error::Socket(format!(
+                    "passed socket {} is an unnamed Unix socket",
+                    fd
+                ))),
+            }
+        }
+        addr => Err(Error::Usage(format!(
+            "passed socket {} has unsupported address {} (only Unix and vsock sockets are supported)",
+            fd, addr
+        ))),
+    }
+}
+
+/// Returns the listening sockets passed by systemd socket activation,
+/// along with the address of each, or an empty list if the process was not
+/// socket activated.
+///
+/// As with `sd_listen_fds(3)`, the `LISTEN_*` variables are removed from
+/// the environment so they are not inherited by child processes.
+pub fn listen_fds() -> Result<Vec<(RawFd, ServerUri)>> {
+    listen_fds_from(SD_LISTEN_FDS_START)
+}
+
+// Implements listen_fds(), with the passed sockets numbered from "start".
+fn listen_fds_from(start: RawFd) -> Result<Vec<(RawFd, ServerUri)>> {
+    let pid = match env::var(LISTEN_PID_ENV) {
+        Ok(pid) => pid,
+        Err(_) => return Ok(Vec::new()),
+    };
+
+    let count = env::var(LISTEN_FDS_ENV).unwrap_or_default();
+
+    env::remove_var(LISTEN_PID_ENV);
+    env::remove_var(LISTEN_FDS_ENV);
+    env::remove_var(LISTEN_FDNAMES_ENV);
+
+    // The sockets are meant for a different process.
+    if pid.parse::<i32>().ok() != Some(getpid().as_raw()) {
+        return Ok(Vec::new());
+    }
+
+    let count: RawFd = match count.parse() {
+        Ok(n) if n >= 0 => n,
+        _ => {
+            return Err(Error::Usage(format!(
+                "invalid {} value: {:?}",
+                LISTEN_FDS_ENV, count
+            )))
+        }
+    };
+
+    let mut fds = Vec::new();
+
+    for fd in start..start + count {
+        if let Err(e) = fcntl(fd, FcntlArg::F_SETFD(FdFlag::FD_CLOEXEC)) {
+            return Err(Error::Socket(format!(
+                "invalid passed socket {}: {:?}",
+                fd, e
+            )));
+        }
+
+        let uri = listen_fd_uri(fd)?;
+
+        info!(sl!(), "adopted socket activated listener";
+            "fd" => fd,
+            "server-uri" => uri.to_string());
+
+        fds.push((fd, uri));
+    }
+
+    Ok(fds)
+}
+
+/// Send `state` (for example [`NOTIFY_READY`]) to the service manager, as
+/// `sd_notify(3)` does. Does nothing unless `NOTIFY_SOCKET` is set.
+pub fn notify(state: &str) -> Result<()> {
+    let path = match env::var(NOTIFY_SOCKET_ENV) {
+        Ok(p) if !p.is_empty() => p,
+        _ => return Ok(()),
+    };
+
+    // A leading "@" denotes an abstract socket, whose name systemd uses
+    // exactly (without a trailing NUL).
+    let sock_addr = match path.strip_prefix('@') {
+        Some(name) => abstract_sock_addr(name, AbstractNaming::Exact)?,
+        None => match UnixAddr::new(path.as_str()) {
+            Ok(addr) => SockAddr::Unix(addr),
+            Err(e) => {
+                return Err(Error::Socket(format!(
+                    "invalid {} value {:?}: {:?}",
+                    NOTIFY_SOCKET_ENV, path, e
+                )))
+            }
+        },
+    };
+
+    let fd = match socket(
+        AddressFamily::Unix,
+        SockType::Datagram,
+        SockFlag::SOCK_CLOEXEC,
+        None,
+    ) {
+        Ok(fd) => fd,
+        Err(e) => {
+            return Err(Error::Socket(format!(
+                "failed to create notify socket: {:?}",
+                e
+            )))
+        }
+    };
+
+    let result = sendto(fd, state.as_bytes(), &sock_addr, MsgFlags::empty());
+
+    let _ = close(fd);
+
+    if let Err(e) = result {
+        return Err(Error::Socket(format!(
+            "failed to send {:?} to {} {:?}: {:?}",
+            state, NOTIFY_SOCKET_ENV, path, e
+        )));
+    }
+
+    info!(sl!(), "notified service manager"; "state" => state);
+
+    Ok(())
+}
+
+// Tests that set the environment variables read here, or that build a
+// server (which reads them), take turns.
+#[cfg(test)]
+pub(crate) static TEST_ENV_LOCK: std::sync::Mutex<()> = std::sync::Mutex::new(());
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::os::unix::io::AsRawFd;
+    use std::os::unix::net::{UnixDatagram, UnixListener};
+    use std::time::Duration;
+    use tempfile::TempDir;
+
+    fn assert_listen_env_removed() {
+        for var in &[LISTEN_PID_ENV, LISTEN_FDS_ENV, LISTEN_FDNAMES_ENV] {
+            assert!(env::var_os(var).is_none(), "{} still set", var);
+        }
+    }
+
+    #[test]
+    fn test_notify() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("notify");
+
+        let sock = UnixDatagram::bind(&path).unwrap();
+
+        sock.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        env::set_var(NOTIFY_SOCKET_ENV, &path);
+
+        let result = notify(NOTIFY_READY);
+
+        env::remove_var(NOTIFY_SOCKET_ENV);
+
+        result.unwrap();
+
+        let mut buf = [0u8; 64];
+
+        let len = sock.recv(&mut buf).unwrap();
+
+        assert_eq!(&buf[..len], NOTIFY_READY.as_bytes());
+    }
+
+    #[test]
+    fn test_notify_without_socket() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+
+        env::remove_var(NOTIFY_SOCKET_ENV);
+
+        notify(NOTIFY_READY).unwrap();
+    }
+
+    #[test]
+    fn test_notify_missing_socket() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+
+        let dir = TempDir::new().unwrap();
+
+        env::set_var(NOTIFY_SOCKET_ENV, dir.path().join("missing"));
+
+        let result = notify(NOTIFY_READY);
+
+        env::remove_var(NOTIFY_SOCKET_ENV);
+
+        match result {
+            Err(Error::Socket(_)) => (),
+            r => panic!("expected socket error, got {:?}", r),
+        }
+    }
+
+    #[test]
+    fn test_listen_fds_for_this_process() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("activated");
+
+        let listener = UnixListener::bind(&path).unwrap();
+        let fd = listener.as_raw_fd();
+
+        env::set_var(LISTEN_PID_ENV, getpid().as_raw().to_string());
+        env::set_var(LISTEN_FDS_ENV, "1");
+        env::set_var(LISTEN_FDNAMES_ENV, "test");
+
+        let fds = listen_fds_from(fd).unwrap();
+
+        assert_listen_env_removed();
+
+        assert_eq!(
+            fds,
+            vec![(fd, ServerUri::UnixPath(path.to_string_lossy().to_string()))]
+        );
+    }
+
+    #[test]
+    fn test_listen_fds_for_another_process() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+
+        let dir = TempDir::new().unwrap();
+
+        let listener = UnixListener::bind(dir.path().join("activated")).unwrap();
+
+        env::set_var(LISTEN_PID_ENV, (getpid().as_raw() + 1).to_string());
+        env::set_var(LISTEN_FDS_ENV, "1");
+
+        let fds = listen_fds_from(listener.as_raw_fd()).unwrap();
+
+        assert_listen_env_removed();
+
+        assert!(fds.is_empty());
+    }
+
+    #[test]
+    fn test_listen_fds_not_activated() {
+        let _lock = TEST_ENV_LOCK.lock().unwrap();
+
+        env::remove_var(LISTEN_PID_ENV);
+
+        assert!(listen_fds().unwrap().is_empty());
+    }
+}
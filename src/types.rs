@@ -1,22 +1,223 @@
 // Description: Common types used by the client and server
 
+use crate::auth::{AccessPolicy, PeerCred};
+use crate::error::exit_code;
+use crate::socket::AbstractNaming;
+use crate::uri::ServerUri;
+use crate::utils::new_request_id;
+use nix::sys::signal::Signal;
+use slog::{info, o, Logger};
+use std::collections::HashMap;
+use std::fmt;
+use std::os::unix::io::RawFd;
+use std::sync::atomic::{AtomicBool, AtomicUsize};
 use std::sync::mpsc::Sender;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub struct Config {
-    pub server_uri: String,
+    pub server_uri: ServerUri,
     pub interactive: bool,
     pub force_abstract_socket: bool,
+    pub abstract_naming: AbstractNaming,
+
+    // If true, allow the server to listen on non-loopback TCP addresses.
+    pub allow_remote_tcp: bool,
 
     // If true, use the "vsock" crate, else use the "nix" crate to handle vsock
     // client comms.
     pub use_vsock_crate_for_vsock: bool,
 
-    pub tx: Option<Sender<bool>>,
+    // Which peers may call each RPC method (server only).
+    pub access_policy: AccessPolicy,
+
+    // How long the server lets in-flight requests finish when shutting
+    // down, unless the shutdown request specifies otherwise.
+    pub shutdown_grace_period: Duration,
+
+    pub tx: Option<Sender<ShutdownParams>>,
 }
 
 #[derive(Debug, Clone)]
 pub struct HelloService {
     pub cfg: Arc<Mutex<Config>>,
+
+    // Number of requests, other than Shutdown requests, currently being
+    // handled. A request counts until its reply has been handed to the
+    // connection.
+    pub in_flight: Arc<AtomicUsize>,
+
+    // Number of Shutdown requests currently being handled. The server
+    // waits for these even when forced to stop, so the caller gets its
+    // reply.
+    pub shutdowns_in_flight: Arc<AtomicUsize>,
+
+    // Set once a shutdown has been requested, after which new requests
+    // are rejected.
+    pub shutting_down: Arc<AtomicBool>,
+}
+
+/// What asked a server to shut down.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ShutdownTrigger {
+    /// A client called the `Shutdown` RPC.
+    Request,
+
+    /// The process received a signal (SIGTERM or SIGINT).
+    Signal(Signal),
+
+    /// The program itself, using [`crate::HelloServer::shutdown_sender`].
+    #[default]
+    Local,
+}
+
+impl ShutdownTrigger {
+    /// The process exit code for a server stopped by this trigger, which
+    /// differs for each: success for a `Shutdown` request, 128 plus the
+    /// signal number (as a shell reports it) for a signal, and
+    /// [`exit_code::LOCAL_SHUTDOWN`] otherwise.
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ShutdownTrigger::Request => exit_code::SUCCESS,
+            ShutdownTrigger::Signal(sig) => exit_code::SIGNAL_BASE + sig as i32,
+            ShutdownTrigger::Local => exit_code::LOCAL_SHUTDOWN,
+        }
+    }
+}
+
+impl fmt::Display for ShutdownTrigger {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ShutdownTrigger::Request => write!(f, "request"),
+            ShutdownTrigger::Signal(sig) => write!(f, "signal {:?}", sig),
+            ShutdownTrigger::Local => write!(f, "local"),
+        }
+    }
+}
+
+/// Why and how a server should shut down.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ShutdownParams {
+    /// What asked for the shutdown.
+    pub trigger: ShutdownTrigger,
+
+    /// Logged by the server.
+    pub reason: String,
+
+    /// How long to let in-flight requests finish (`None` means the
+    /// server's default).
+    pub grace_period: Option<Duration>,
+
+    /// Shut down without waiting for in-flight requests.
+    pub force: bool,
+}
+
+/// Key/value metadata carried by a ttRPC request. A key may have several
+/// values.
+pub type Metadata = HashMap<String, Vec<String>>;
+
+/// The metadata key (and log field) of the ID that ties a client command
+/// to the server's handling of the requests it makes.
+pub const REQUEST_ID_KEY: &str = "request-id";
+
+/// What a request handler knows about the request it is serving.
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// The connection the request arrived on.
+    pub fd: RawFd,
+
+    /// The metadata sent with the request.
+    pub metadata: Metadata,
+
+    /// The ID the client sent as [`REQUEST_ID_KEY`] metadata, or one made
+    /// up by the server if it sent none.
+    pub request_id: String,
+}
+
+impl RequestContext {
+    pub fn new(fd: RawFd, metadata: Metadata) -> Self {
+        let request_id = metadata
+            .get(REQUEST_ID_KEY)
+            .and_then(|values| values.first())
+            .filter(|id| !id.is_empty())
+            .cloned()
+            .unwrap_or_else(new_request_id);
+
+        RequestContext {
+            fd,
+            metadata,
+            request_id,
+        }
+    }
+
+    /// A child of the scope logger that logs the request ID.
+    pub fn logger(&self) -> Logger {
+        sl!().new(o!(REQUEST_ID_KEY => self.request_id.clone()))
+    }
+
+    /// Call `f` with [`RequestContext::logger`] as the scope logger, so
+    /// everything it logs carries the request ID.
+    pub fn scope<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        slog_scope::scope(&self.logger(), f)
+    }
+
+    /// The credentials of the peer that sent the request, or `None` if it
+    /// has none (see [`PeerCred::from_fd`]).
+    pub fn peer(&self) -> Option<PeerCred> {
+        PeerCred::from_fd(self.fd)
+    }
+
+    // Log a call of "method" of "service", along with the peer making it
+    // and the metadata it sent. Returns the peer's credentials.
+    pub(crate) fn log_request(&self, service: &str, method: &str) -> Option<PeerCred> {
+        let peer = self.peer();
+
+        let peer_desc = match &peer {
+            Some(p) => p.to_string(),
+            None => "unknown".to_string(),
+        };
+
+        info!(sl!(), "request";
+            "service" => service,
+            "method" => method,
+            "peer" => peer_desc,
+            "metadata" => format!("{:?}", self.metadata));
+
+        peer
+    }
+
+    /// The first value of metadata `key`, if it was sent.
+    pub fn metadata_value(&self, key: &str) -> Option<&str> {
+        self.metadata
+            .get(key)
+            .and_then(|values| values.first())
+            .map(|v| v.as_str())
+    }
+}
+
+/// How the client retries connecting to a server that is not yet listening.
+///
+/// Retries stop after `max_attempts` attempts, or once `deadline` has
+/// passed since the first attempt, whichever comes first. The delay between
+/// attempts starts at `initial_backoff` and doubles each time, up to
+/// `max_backoff`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    pub deadline: Option<Duration>,
+}
+
+impl Default for RetryPolicy {
+    // Connect once, without retrying.
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            deadline: None,
+        }
+    }
 }
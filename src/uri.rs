@@ -0,0 +1,562 @@
+// Description: Server URI parsing shared by the client and server
+
+use crate::error::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+
+const SCHEME_SEPARATOR: &str = "://";
+
+const UNIX_SCHEME: &str = "unix";
+const VSOCK_SCHEME: &str = "vsock";
+const HYBRID_VSOCK_SCHEME: &str = "hvsock";
+const TCP_SCHEME: &str = "tcp";
+
+// Prefix denoting an abstract Unix socket name.
+const ABSTRACT_PREFIX: char = '@';
+
+// Enclose an IPv6 address in a TCP URI.
+const IPV6_OPEN_BRACKET: char = '[';
+const IPV6_CLOSE_BRACKET: char = ']';
+
+/// The CID for communicating with the local host over the
+/// "vsock_loopback" transport (not yet defined by the libc crate).
+pub const VMADDR_CID_LOCAL: u32 = 1;
+
+// Aliases for libc::VMADDR_CID_ANY.
+const VSOCK_CID_ANY_ALIASES: &[&str] = &["-1", ""];
+
+// Alias for libc::VMADDR_PORT_ANY.
+const VSOCK_PORT_ANY_ALIAS: &str = "any";
+
+/// A parsed server address.
+///
+/// Valid formats:
+///
+/// - `unix:///path/to/socket` (named Unix socket)
+/// - `unix://@name` (abstract Unix socket)
+/// - `vsock://cid:port` (where a cid of `-1` or an empty cid means
+///   `VMADDR_CID_ANY`, a cid of `1` means [`VMADDR_CID_LOCAL`] and a port of
+///   `any` means `VMADDR_PORT_ANY`, letting the kernel choose the port)
+/// - `hvsock:///path/to/uds:port` (hybrid vsock, as used by Firecracker and
+///   Cloud Hypervisor: a Unix socket on the host that forwards to a vsock
+///   port in the guest)
+/// - `tcp://host:port` (where an IPv6 host must be in brackets, for example
+///   `tcp://[::1]:1024`, and a port of `0` lets the kernel choose the port)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ServerUri {
+    UnixPath(String),
+
+    /// The abstract socket name, without the leading `@`.
+    UnixAbstract(String),
+
+    Vsock {
+        cid: u32,
+        port: u32,
+    },
+
+    HybridVsock {
+        path: String,
+        port: u32,
+    },
+
+    /// The host name or address, without any IPv6 brackets.
+    Tcp {
+        host: String,
+        port: u16,
+    },
+}
+
+impl ServerUri {
+    /// Convert a named Unix socket into an abstract one with the same name,
+    /// as `--abstract` requests. Other addresses are returned unchanged.
+    pub fn into_abstract(self) -> ServerUri {
+        match self {
+            ServerUri::UnixPath(path) => ServerUri::UnixAbstract(path),
+            uri => uri,
+        }
+    }
+}
+
+fn parse_vsock_field(uri: &str, field: &str, value: &str) -> Result<u32> {
+    match value.parse::<u32>() {
+        Ok(n) => Ok(n),
+        Err(e) => Err(Error::Uri(format!(
+            "VSOCK {} {:?} is not numeric ({}): {:?}",
+            field, value, e, uri
+        ))),
+    }
+}
+
+// Parse the host part of a TCP URI: a name or IPv4 address, or an IPv6
+// address in a single pair of brackets.
+fn parse_tcp_host<'a>(uri: &str, host: &'a str) -> Result<&'a str> {
+    let bracketed = host
+        .strip_prefix(IPV6_OPEN_BRACKET)
+        .and_then(|h| h.strip_suffix(IPV6_CLOSE_BRACKET));
+
+    let (host, has_brackets) = match bracketed {
+        Some(h) => (h, true),
+        None => (host, false),
+    };
+
+    if host.contains(&[IPV6_OPEN_BRACKET, IPV6_CLOSE_BRACKET][..]) {
+        return Err(Error::Uri(format!(
+            "TCP host must be an address or name, or an IPv6 address in one pair of brackets: {:?}",
+            uri
+        )));
+    }
+
+    if host.is_empty() {
+        return Err(Error::Uri(format!("empty TCP host: {:?}", uri)));
+    }
+
+    if host.contains(':') && !has_brackets {
+        return Err(Error::Uri(format!(
+            "IPv6 TCP host must be in brackets (for example {}{}[::1]:1024): {:?}",
+            TCP_SCHEME, SCHEME_SEPARATOR, uri
+        )));
+    }
+
+    Ok(host)
+}
+
+impl FromStr for ServerUri {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let uri = s.trim();
+
+        let (scheme, rest) = match uri.find(SCHEME_SEPARATOR) {
+            Some(i) => (&uri[..i], &uri[i + SCHEME_SEPARATOR.len()..]),
+            None => {
+                return Err(Error::Uri(format!(
+                    "missing {:?} after scheme: {:?}",
+                    SCHEME_SEPARATOR, s
+                )))
+            }
+        };
+
+        match scheme.to_lowercase().as_str() {
+            UNIX_SCHEME => {
+                if rest.is_empty() {
+                    return Err(Error::Uri(format!("empty Unix socket path: {:?}", s)));
+                }
+
+                if rest.starts_with(ABSTRACT_PREFIX) {
+                    let name = &rest[ABSTRACT_PREFIX.len_utf8()..];
+
+                    if name.is_empty() {
+                        return Err(Error::Uri(format!(
+                            "empty abstract Unix socket name after {:?}: {:?}",
+                            ABSTRACT_PREFIX, s
+                        )));
+                    }
+
+                    return Ok(ServerUri::UnixAbstract(name.to_string()));
+                }
+
+                Ok(ServerUri::UnixPath(rest.to_string()))
+            }
+            VSOCK_SCHEME => {
+                let addr: Vec<&str> = rest.split(':').collect();
+                if addr.len() != 2 {
+                    return Err(Error::Uri(format!(
+                        "expected {}{}cid:port: {:?}",
+                        VSOCK_SCHEME, SCHEME_SEPARATOR, s
+                    )));
+                }
+
+                let cid = if VSOCK_CID_ANY_ALIASES.contains(&addr[0]) {
+                    libc::VMADDR_CID_ANY
+                } else {
+                    parse_vsock_field(s, "cid", addr[0])?
+                };
+
+                let port = if addr[1] == VSOCK_PORT_ANY_ALIAS {
+                    libc::VMADDR_PORT_ANY
+                } else {
+                    parse_vsock_field(s, "port", addr[1])?
+                };
+
+                Ok(ServerUri::Vsock { cid, port })
+            }
+            HYBRID_VSOCK_SCHEME => {
+                let (path, port) = match rest.rfind(':') {
+                    Some(i) => (&rest[..i], &rest[i + 1..]),
+                    None => {
+                        return Err(Error::Uri(format!(
+                            "expected {}{}path:port: {:?}",
+                            HYBRID_VSOCK_SCHEME, SCHEME_SEPARATOR, s
+                        )))
+                    }
+                };
+
+                if path.is_empty() {
+                    return Err(Error::Uri(format!(
+                        "empty hybrid vsock Unix socket path: {:?}",
+                        s
+                    )));
+                }
+
+                let port = parse_vsock_field(s, "port", port)?;
+
+                Ok(ServerUri::HybridVsock {
+                    path: path.to_string(),
+                    port,
+                })
+            }
+            TCP_SCHEME => {
+                let (host, port) = match rest.rfind(':') {
+                    Some(i) => (&rest[..i], &rest[i + 1..]),
+                    None => {
+                        return Err(Error::Uri(format!(
+                            "expected {}{}host:port: {:?}",
+                            TCP_SCHEME, SCHEME_SEPARATOR, s
+                        )))
+                    }
+                };
+
+                let host = parse_tcp_host(s, host)?;
+
+                let port = match port.parse::<u16>() {
+                    Ok(p) => p,
+                    Err(e) => {
+                        return Err(Error::Uri(format!(
+                            "TCP port {:?} is not a valid port number ({}): {:?}",
+                            port, e, s
+                        )))
+                    }
+                };
+
+                Ok(ServerUri::Tcp {
+                    host: host.to_string(),
+                    port,
+                })
+            }
+            _ => Err(Error::Uri(format!(
+                "invalid address scheme {:?} (expected {:?}, {:?}, {:?} or {:?}): {:?}",
+                scheme, UNIX_SCHEME, VSOCK_SCHEME, HYBRID_VSOCK_SCHEME, TCP_SCHEME, s
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ServerUri {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ServerUri::UnixPath(path) => write!(f, "{}{}{}", UNIX_SCHEME, SCHEME_SEPARATOR, path),
+            ServerUri::UnixAbstract(name) => write!(
+                f,
+                "{}{}{}{}",
+                UNIX_SCHEME, SCHEME_SEPARATOR, ABSTRACT_PREFIX, name
+            ),
+            ServerUri::Vsock { cid, port } => {
+                let cid = match *cid {
+                    libc::VMADDR_CID_ANY => VSOCK_CID_ANY_ALIASES[0].to_string(),
+                    cid => cid.to_string(),
+                };
+
+                let port = match *port {
+                    libc::VMADDR_PORT_ANY => VSOCK_PORT_ANY_ALIAS.to_string(),
+                    port => port.to_string(),
+                };
+
+                write!(f, "{}{}{}:{}", VSOCK_SCHEME, SCHEME_SEPARATOR, cid, port)
+            }
+            ServerUri::HybridVsock { path, port } => write!(
+                f,
+                "{}{}{}:{}",
+                HYBRID_VSOCK_SCHEME, SCHEME_SEPARATOR, path, port
+            ),
+            ServerUri::Tcp { host, port } if host.contains(':') => write!(
+                f,
+                "{}{}{}{}{}:{}",
+                TCP_SCHEME, SCHEME_SEPARATOR, IPV6_OPEN_BRACKET, host, IPV6_CLOSE_BRACKET, port
+            ),
+            ServerUri::Tcp { host, port } => {
+                write!(f, "{}{}{}:{}", TCP_SCHEME, SCHEME_SEPARATOR, host, port)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::net::Ipv6Addr;
+
+    fn parse(s: &str) -> Result<ServerUri> {
+        s.parse()
+    }
+
+    // Assert "s" fails to parse with an error mentioning "needle".
+    fn assert_invalid(s: &str, needle: &str) {
+        match parse(s) {
+            Err(Error::Uri(msg)) => assert!(
+                msg.contains(needle),
+                "error for {:?} does not mention {:?}: {}",
+                s,
+                needle,
+                msg
+            ),
+            r => panic!("expected URI error for {:?}, got {:?}", s, r),
+        }
+    }
+
+    fn assert_round_trip(uri: &ServerUri) {
+        let s = uri.to_string();
+
+        assert_eq!(
+            &parse(&s).unwrap(),
+            uri,
+            "round trip of {:?} via {:?}",
+            uri,
+            s
+        );
+    }
+
+    #[test]
+    fn test_round_trip_each_variant() {
+        let uris = vec![
+            ServerUri::UnixPath("/tmp/foo.socket".to_string()),
+            ServerUri::UnixPath("relative/path".to_string()),
+            ServerUri::UnixAbstract("foo".to_string()),
+            ServerUri::UnixAbstract("@starts-with-at".to_string()),
+            ServerUri::Vsock { cid: 3, port: 1024 },
+            ServerUri::Vsock {
+                cid: VMADDR_CID_LOCAL,
+                port: 0,
+            },
+            ServerUri::Vsock {
+                cid: libc::VMADDR_CID_ANY,
+                port: libc::VMADDR_PORT_ANY,
+            },
+            ServerUri::HybridVsock {
+                path: "/run/vm/vsock.sock".to_string(),
+                port: 1024,
+            },
+            ServerUri::HybridVsock {
+                path: "/path:with:colons".to_string(),
+                port: u32::MAX - 1,
+            },
+            ServerUri::Tcp {
+                host: "localhost".to_string(),
+                port: 1024,
+            },
+            ServerUri::Tcp {
+                host: "127.0.0.1".to_string(),
+                port: 0,
+            },
+            ServerUri::Tcp {
+                host: "::1".to_string(),
+                port: u16::MAX,
+            },
+            ServerUri::Tcp {
+                host: "fe80::1:2".to_string(),
+                port: 80,
+            },
+        ];
+
+        for uri in &uris {
+            assert_round_trip(uri);
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let tests = vec![
+            (ServerUri::UnixPath("/a".to_string()), "unix:///a"),
+            (ServerUri::UnixAbstract("a".to_string()), "unix://@a"),
+            (ServerUri::Vsock { cid: 2, port: 3 }, "vsock://2:3"),
+            (
+                ServerUri::Vsock {
+                    cid: libc::VMADDR_CID_ANY,
+                    port: libc::VMADDR_PORT_ANY,
+                },
+                "vsock://-1:any",
+            ),
+            (
+                ServerUri::HybridVsock {
+                    path: "/a".to_string(),
+                    port: 3,
+                },
+                "hvsock:///a:3",
+            ),
+            (
+                ServerUri::Tcp {
+                    host: "::1".to_string(),
+                    port: 3,
+                },
+                "tcp://[::1]:3",
+            ),
+            (
+                ServerUri::Tcp {
+                    host: "a".to_string(),
+                    port: 3,
+                },
+                "tcp://a:3",
+            ),
+        ];
+
+        for (uri, expected) in tests {
+            assert_eq!(uri.to_string(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_valid() {
+        let tests = vec![
+            ("unix:///tmp/a", ServerUri::UnixPath("/tmp/a".to_string())),
+            ("unix://@a", ServerUri::UnixAbstract("a".to_string())),
+            ("unix://@@a", ServerUri::UnixAbstract("@a".to_string())),
+            ("UNIX:///a", ServerUri::UnixPath("/a".to_string())),
+            ("  unix:///a  ", ServerUri::UnixPath("/a".to_string())),
+            ("vsock://3:1024", ServerUri::Vsock { cid: 3, port: 1024 }),
+            (
+                "vsock://-1:1024",
+                ServerUri::Vsock {
+                    cid: libc::VMADDR_CID_ANY,
+                    port: 1024,
+                },
+            ),
+            (
+                "vsock://:1024",
+                ServerUri::Vsock {
+                    cid: libc::VMADDR_CID_ANY,
+                    port: 1024,
+                },
+            ),
+            (
+                "vsock://3:any",
+                ServerUri::Vsock {
+                    cid: 3,
+                    port: libc::VMADDR_PORT_ANY,
+                },
+            ),
+            (
+                "hvsock:///a:b:5",
+                ServerUri::HybridVsock {
+                    path: "/a:b".to_string(),
+                    port: 5,
+                },
+            ),
+            (
+                "tcp://[::1]:1024",
+                ServerUri::Tcp {
+                    host: "::1".to_string(),
+                    port: 1024,
+                },
+            ),
+            (
+                "tcp://[localhost]:1024",
+                ServerUri::Tcp {
+                    host: "localhost".to_string(),
+                    port: 1024,
+                },
+            ),
+            (
+                "tcp://127.0.0.1:0",
+                ServerUri::Tcp {
+                    host: "127.0.0.1".to_string(),
+                    port: 0,
+                },
+            ),
+        ];
+
+        for (s, expected) in tests {
+            assert_eq!(parse(s).unwrap(), expected, "parsing {:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let tests = vec![
+            ("", "missing"),
+            ("/tmp/a", "missing"),
+            ("unix:/tmp/a", "missing"),
+            ("vsock:3:1024", "missing"),
+            ("foo:///a", "invalid address scheme"),
+            ("unix://", "empty Unix socket path"),
+            ("unix://@", "empty abstract Unix socket name"),
+            ("vsock://3", "expected vsock://cid:port"),
+            ("vsock://3:4:5", "expected vsock://cid:port"),
+            ("vsock://x:1024", "cid \"x\" is not numeric"),
+            ("vsock://-2:1024", "cid \"-2\" is not numeric"),
+            (
+                "vsock://4294967296:1024",
+                "cid \"4294967296\" is not numeric",
+            ),
+            ("vsock://3:x", "port \"x\" is not numeric"),
+            ("vsock://3:", "port \"\" is not numeric"),
+            ("vsock://3:-1", "port \"-1\" is not numeric"),
+            ("vsock://any:1024", "cid \"any\" is not numeric"),
+            ("hvsock:///a", "expected hvsock://path:port"),
+            ("hvsock://:5", "empty hybrid vsock Unix socket path"),
+            ("hvsock:///a:x", "port \"x\" is not numeric"),
+            ("hvsock:///a:any", "port \"any\" is not numeric"),
+            ("tcp://localhost", "expected tcp://host:port"),
+            ("tcp://:1024", "empty TCP host"),
+            ("tcp://[]:1024", "empty TCP host"),
+            ("tcp://localhost:x", "not a valid port number"),
+            ("tcp://localhost:65536", "not a valid port number"),
+            ("tcp://localhost:", "not a valid port number"),
+            ("tcp://::1:1024", "must be in brackets"),
+            ("tcp://[[::1]]:1024", "one pair of brackets"),
+            ("tcp://[::1:1024", "one pair of brackets"),
+            ("tcp://::1]:1024", "one pair of brackets"),
+            ("tcp://[::1]]:1024", "one pair of brackets"),
+            ("tcp://a[b]:1024", "one pair of brackets"),
+        ];
+
+        for (s, needle) in tests {
+            assert_invalid(s, needle);
+        }
+    }
+
+    #[test]
+    fn test_into_abstract() {
+        assert_eq!(
+            ServerUri::UnixPath("/a".to_string()).into_abstract(),
+            ServerUri::UnixAbstract("/a".to_string())
+        );
+
+        let vsock = ServerUri::Vsock { cid: 3, port: 4 };
+
+        assert_eq!(vsock.clone().into_abstract(), vsock);
+    }
+
+    // Every URI Display can produce: socket paths and names are non-empty
+    // without surrounding space (which parsing trims), and a path may not
+    // start with the abstract prefix.
+    fn any_uri() -> impl Strategy<Value = ServerUri> {
+        let host = prop_oneof![
+            "[a-zA-Z0-9]([a-zA-Z0-9.-]{0,30}[a-zA-Z0-9])?",
+            any::<[u8; 4]>().prop_map(|a| format!("{}.{}.{}.{}", a[0], a[1], a[2], a[3])),
+            any::<[u16; 8]>().prop_map(|a| Ipv6Addr::from(a).to_string()),
+        ];
+
+        prop_oneof![
+            "[^@\\s]([^\\s]{0,40}[^\\s])?".prop_map(ServerUri::UnixPath),
+            "[^\\s]([^\\s]{0,40}[^\\s])?".prop_map(ServerUri::UnixAbstract),
+            (any::<u32>(), any::<u32>()).prop_map(|(cid, port)| ServerUri::Vsock { cid, port }),
+            ("[^\\s]([^\\s]{0,40}[^\\s])?", any::<u32>())
+                .prop_map(|(path, port)| ServerUri::HybridVsock { path, port }),
+            (host, any::<u16>()).prop_map(|(host, port)| ServerUri::Tcp { host, port }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn test_display_from_str_round_trip(uri in any_uri()) {
+            let s = uri.to_string();
+
+            prop_assert_eq!(s.parse::<ServerUri>().unwrap(), uri);
+        }
+
+        #[test]
+        fn test_from_str_never_panics(s in "\\PC{0,60}") {
+            let _ = s.parse::<ServerUri>();
+        }
+    }
+}
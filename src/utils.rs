@@ -0,0 +1,73 @@
+// Description: Miscellaneous helpers
+
+use crate::error::{Error, Result};
+use std::process;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+// Parse a duration such as "500ms", "5s" or "2m". A bare number is taken
+// to be a number of seconds.
+pub fn parse_duration(value: &str) -> Result<Duration> {
+    let value = value.trim();
+
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+
+    let (num, unit) = value.split_at(split);
+
+    let n: u64 = match num.parse() {
+        Ok(n) => n,
+        Err(_) => return Err(Error::Usage(format!("invalid duration: {:?}", value))),
+    };
+
+    let secs = |mult: u64| match n.checked_mul(mult) {
+        Some(s) => Ok(Duration::from_secs(s)),
+        None => Err(Error::Usage(format!("duration too large: {:?}", value))),
+    };
+
+    match unit {
+        "ns" => Ok(Duration::from_nanos(n)),
+        "us" => Ok(Duration::from_micros(n)),
+        "ms" => Ok(Duration::from_millis(n)),
+        "" | "s" => secs(1),
+        "m" => secs(60),
+        "h" => secs(60 * 60),
+        _ => Err(Error::Usage(format!(
+            "invalid duration unit {:?} (expected ns, us, ms, s, m or h)",
+            unit
+        ))),
+    }
+}
+
+/// Create an ID for a request, unique to this process and unlikely to be
+/// reused by another: the process ID, the time and a sequence number.
+pub fn new_request_id() -> String {
+    static NEXT: AtomicU64 = AtomicU64::new(1);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default();
+
+    format!(
+        "{:x}-{:x}-{:x}",
+        process::id(),
+        nanos,
+        NEXT.fetch_add(1, Ordering::SeqCst)
+    )
+}
+
+/// Create the multi-threaded tokio runtime used by the async server and
+/// client.
+#[cfg(feature = "async")]
+pub fn new_runtime() -> Result<tokio::runtime::Runtime> {
+    match tokio::runtime::Builder::new()
+        .threaded_scheduler()
+        .enable_all()
+        .build()
+    {
+        Ok(rt) => Ok(rt),
+        Err(e) => Err(Error::Io(format!("failed to create tokio runtime: {}", e))),
+    }
+}